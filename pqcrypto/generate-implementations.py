@@ -72,7 +72,7 @@ def generate_scheme(name, type, properties):
     render_template(
         target_dir, 'Cargo.toml', 'scheme/Cargo.toml.j2',
         traits_version=implementations['traits_version'],
-        # internals_version=implementations['internals_version'],
+        internals_version=implementations['internals_version'],
         name=name,
         type=type,
         version=properties['version'],
@@ -105,6 +105,7 @@ def generate_scheme(name, type, properties):
             type=type,
             name=name,
             scheme=scheme,
+            metadata=metadatas[scheme['name']],
         )
 
     render_template(
@@ -0,0 +1,8 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    // exposed to the scheme crates' build scripts as DEP_PQCRYPTO_INTERNALS_INCLUDEPATH
+    let include_dir: PathBuf = [env::var("CARGO_MANIFEST_DIR").unwrap().as_str(), "include"].iter().collect();
+    println!("cargo:includepath={}", include_dir.display());
+}
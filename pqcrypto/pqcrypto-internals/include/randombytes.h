@@ -0,0 +1,10 @@
+#ifndef PQCRYPTO_RUST_RANDOMBYTES_H
+#define PQCRYPTO_RUST_RANDOMBYTES_H
+#include <stddef.h>
+#include <stdint.h>
+
+// Replaces PQClean's common/randombytes.h: randomness comes from pqcrypto-internals
+#define randombytes PQCRYPTO_RUST_randombytes
+int PQCRYPTO_RUST_randombytes(uint8_t *buf, size_t n);
+
+#endif
@@ -0,0 +1,312 @@
+//! Shared internals of the pqcrypto crates
+//!
+//! This crate provides the ``randombytes`` function that the PQClean implementations call. By default it
+//! draws from the operating system. [with_rng] instead draws from a caller-supplied RNG, e.g. one backed by
+//! an HSM, and [with_seeded_randombytes] from a NIST AES-256 CTR_DRBG (the generator used to produce the
+//! NIST known-answer tests), so that key generation and encapsulation become reproducible.
+//!
+//! Without the default ``std`` feature, ``randombytes`` always draws from [getrandom], and [with_rng] and
+//! [with_seeded_randombytes] are unavailable. On targets without an operating system, enable the
+//! ``custom-getrandom`` feature and register a source with ``getrandom::register_custom_getrandom!``.
+//!
+//! PQClean does not check whether ``randombytes`` succeeded, and would carry on with predictable output. So if
+//! the source fails, or the RNG given to [with_rng] panics, the process is aborted instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+#[cfg(feature = "std")]
+use aes::Aes256;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+pub use rand_core::{self, CryptoRng, RngCore};
+#[cfg(feature = "std")]
+use zeroize::Zeroize;
+
+/// The length of the seed accepted by [with_seeded_randombytes]
+pub const SEED_BYTES: usize = 48;
+
+#[cfg(feature = "std")]
+const KEY_BYTES: usize = 32;
+#[cfg(feature = "std")]
+const BLOCK_BYTES: usize = 16;
+
+/// Where ``randombytes`` draws from while a source is installed on this thread
+#[cfg(feature = "std")]
+enum Source {
+    Drbg(CtrDrbg),
+    /// Only ever installed by [with_rng], which removes it before the borrow ends
+    Rng(*mut (dyn RngCore + 'static)),
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static SOURCE: RefCell<Option<Source>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "std")]
+/// Runs `f` with every ``randombytes`` call made on this thread drawing from a DRBG seeded with `seed`.
+/// Other threads are unaffected, and the previous source is restored afterwards, even if `f` panics
+pub fn with_seeded_randombytes<T>(seed: &[u8; SEED_BYTES], f: impl FnOnce() -> T) -> T {
+    with_source(Source::Drbg(CtrDrbg::new(seed)), f)
+}
+
+#[cfg(feature = "std")]
+/// Runs `f` with every ``randombytes`` call made on this thread drawing from `rng`.
+/// Other threads are unaffected, and the previous source is restored afterwards, even if `f` panics
+pub fn with_rng<R: RngCore + CryptoRng + ?Sized, T>(rng: &mut R, f: impl FnOnce() -> T) -> T {
+    let mut rng = Forward(rng);
+    let rng: *mut (dyn RngCore + '_) = &mut rng;
+    // SAFETY: with_source uninstalls the source before returning or unwinding, so the pointer never outlives `rng`
+    let rng: *mut (dyn RngCore + 'static) = unsafe { std::mem::transmute(rng) };
+    with_source(Source::Rng(rng), f)
+}
+
+#[cfg(feature = "std")]
+fn with_source<T>(source: Source, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Source>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SOURCE.with(|source| *source.borrow_mut() = previous);
+        }
+    }
+
+    let previous = SOURCE.with(|current| current.borrow_mut().replace(source));
+    let _restore = Restore(previous);
+    f()
+}
+
+#[cfg(feature = "std")]
+/// Lets an unsized RNG, such as a trait object, be used where a sized one is needed
+struct Forward<'a, R: ?Sized>(&'a mut R);
+
+#[cfg(feature = "std")]
+impl<R: RngCore + ?Sized> RngCore for Forward<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// Fills `buf` with `n` random bytes. Called by the PQClean implementations. Aborts the process if no random
+/// bytes can be drawn, since PQClean ignores the return value
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `n` bytes
+#[no_mangle]
+pub unsafe extern "C" fn PQCRYPTO_RUST_randombytes(buf: *mut u8, n: usize) -> core::ffi::c_int {
+    if n == 0 {
+        return 0;
+    }
+
+    let output = core::slice::from_raw_parts_mut(buf, n);
+    // a panic in a caller-supplied RNG must not unwind into C
+    #[cfg(feature = "std")]
+    let filled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| fill(output))).unwrap_or(false);
+    #[cfg(not(feature = "std"))]
+    let filled = fill(output);
+
+    if !filled {
+        abort();
+    }
+
+    0
+}
+
+#[cfg(feature = "std")]
+fn abort() -> ! {
+    std::process::abort()
+}
+
+#[cfg(not(feature = "std"))]
+fn abort() -> ! {
+    // this panic cannot unwind out of PQCRYPTO_RUST_randombytes: it either ends in a panic handler that does not
+    // return, or is turned into an abort at the extern "C" boundary
+    panic!("randombytes: no random bytes available")
+}
+
+#[cfg(feature = "std")]
+fn fill(output: &mut [u8]) -> bool {
+    let (filled, rng) = SOURCE.with(|source| match source.borrow_mut().as_mut() {
+        Some(Source::Drbg(drbg)) => {
+            drbg.fill(output);
+            (true, None)
+        }
+        Some(Source::Rng(rng)) => (false, Some(*rng)),
+        None => (false, None),
+    });
+
+    filled || match rng {
+        // SAFETY: the RNG is borrowed for as long as it is installed, see with_rng. It is called outside of
+        // the RefCell borrow in case it draws from PQClean itself
+        Some(rng) => unsafe { (*rng).try_fill_bytes(output).is_ok() },
+        None => getrandom::getrandom(output).is_ok(),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn fill(output: &mut [u8]) -> bool {
+    getrandom::getrandom(output).is_ok()
+}
+
+#[cfg(feature = "std")]
+/// The AES-256 CTR_DRBG from NIST's ``rng.c``, without a derivation function or reseeding
+struct CtrDrbg {
+    key: [u8; KEY_BYTES],
+    v: [u8; BLOCK_BYTES],
+}
+
+#[cfg(feature = "std")]
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+    }
+}
+
+#[cfg(feature = "std")]
+impl CtrDrbg {
+    fn new(seed: &[u8; SEED_BYTES]) -> Self {
+        let mut drbg = Self { key: [0u8; KEY_BYTES], v: [0u8; BLOCK_BYTES] };
+        drbg.update(Some(seed));
+        drbg
+    }
+
+    fn fill(&mut self, output: &mut [u8]) {
+        let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+        for chunk in output.chunks_mut(BLOCK_BYTES) {
+            let block = self.next_block(&cipher);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        self.update(None);
+    }
+
+    fn update(&mut self, provided_data: Option<&[u8; SEED_BYTES]>) {
+        let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+        let mut temp = [0u8; SEED_BYTES];
+        for chunk in temp.chunks_mut(BLOCK_BYTES) {
+            chunk.copy_from_slice(&self.next_block(&cipher));
+        }
+
+        if let Some(provided_data) = provided_data {
+            temp.iter_mut().zip(provided_data.iter()).for_each(|(byte, data)| *byte ^= data);
+        }
+
+        self.key.copy_from_slice(&temp[..KEY_BYTES]);
+        self.v.copy_from_slice(&temp[KEY_BYTES..]);
+        temp.zeroize();
+    }
+
+    /// Increments V as a big-endian counter and encrypts it
+    fn next_block(&mut self, cipher: &Aes256) -> GenericArray<u8, aes::cipher::consts::U16> {
+        for byte in self.v.iter_mut().rev() {
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+
+        let mut block = GenericArray::clone_from_slice(&self.v);
+        cipher.encrypt_block(&mut block);
+        block
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The first ``randombytes(seed, 48)`` output of NIST's ``PQCgenKAT_kem``, seeded with 0, 1, ..., 47
+    #[test]
+    pub fn test_nist_kat_seed() {
+        let mut entropy = [0u8; SEED_BYTES];
+        entropy.iter_mut().enumerate().for_each(|(i, byte)| *byte = i as u8);
+
+        let mut seed = [0u8; SEED_BYTES];
+        CtrDrbg::new(&entropy).fill(&mut seed);
+        let expected = [
+            0x06, 0x15, 0x50, 0x23, 0x4D, 0x15, 0x8C, 0x5E, 0xC9, 0x55, 0x95, 0xFE,
+            0x04, 0xEF, 0x7A, 0x25, 0x76, 0x7F, 0x2E, 0x24, 0xCC, 0x2B, 0xC4, 0x79,
+            0xD0, 0x9D, 0x86, 0xDC, 0x9A, 0xBC, 0xFD, 0xE7, 0x05, 0x6A, 0x8C, 0x26,
+            0x6F, 0x9E, 0xF9, 0x7E, 0xD0, 0x85, 0x41, 0xDB, 0xD2, 0xE1, 0xFF, 0xA1,
+        ];
+        assert_eq!(seed, expected);
+    }
+
+    #[test]
+    pub fn test_seeded_randombytes() {
+        let seed = [7u8; SEED_BYTES];
+        let draw = || {
+            let mut output = [0u8; 100];
+            assert_eq!(unsafe { PQCRYPTO_RUST_randombytes(output.as_mut_ptr(), output.len()) }, 0);
+            output
+        };
+
+        let first = with_seeded_randombytes(&seed, draw);
+        assert_eq!(with_seeded_randombytes(&seed, draw), first);
+        // the operating system source is restored afterwards
+        assert_ne!(draw(), first);
+    }
+
+    /// Counts up from zero, one byte at a time
+    struct CountingRng(u8);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for CountingRng {}
+
+    #[test]
+    pub fn test_with_rng() {
+        let mut rng = CountingRng(0);
+        let mut output = [0xFFu8; 4];
+        let nested = with_rng(&mut rng, || {
+            unsafe { PQCRYPTO_RUST_randombytes(output.as_mut_ptr(), output.len()) };
+            // an inner source takes over until it returns
+            let mut inner = [0u8; 2];
+            with_seeded_randombytes(&[0u8; SEED_BYTES], || unsafe { PQCRYPTO_RUST_randombytes(inner.as_mut_ptr(), inner.len()) });
+            let mut after = [0u8; 2];
+            unsafe { PQCRYPTO_RUST_randombytes(after.as_mut_ptr(), after.len()) };
+            after
+        });
+
+        assert_eq!(output, [0, 1, 2, 3]);
+        assert_eq!(nested, [4, 5]);
+        assert_eq!(rng.0, 6);
+    }
+}
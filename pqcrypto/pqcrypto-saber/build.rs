@@ -1,15 +1,19 @@
 extern crate cc;
 extern crate glob;
 
+use std::env;
 use std::path::PathBuf;
 
 fn main() {
+    // randombytes is provided by pqcrypto-internals, whose header must shadow the one in common
+    let internals_include_dir: PathBuf = env::var("DEP_PQCRYPTO_INTERNALS_INCLUDEPATH")
+        .unwrap()
+        .into();
     let common_dir: PathBuf = ["pqclean", "common"].iter().collect();
     let common_files = vec![
         common_dir.join("fips202.c"),
         common_dir.join("aes.c"),
         common_dir.join("sha2.c"),
-        common_dir.join("randombytes.c"),
         common_dir.join("sp800-185.c"),
     ];
 
@@ -24,11 +28,15 @@ fn main() {
             .iter()
             .collect();
         let scheme_files = glob::glob(target_dir.join("*.c").to_str().unwrap()).unwrap();
-        builder.include(&common_dir).include(target_dir).files(
-            scheme_files
-                .into_iter()
-                .map(|p| p.unwrap().to_string_lossy().into_owned()),
-        );
+        builder
+            .include(&internals_include_dir)
+            .include(&common_dir)
+            .include(target_dir)
+            .files(
+                scheme_files
+                    .into_iter()
+                    .map(|p| p.unwrap().to_string_lossy().into_owned()),
+            );
         builder.compile("firesaber_clean");
     }
 
@@ -39,11 +47,15 @@ fn main() {
             .iter()
             .collect();
         let scheme_files = glob::glob(target_dir.join("*.c").to_str().unwrap()).unwrap();
-        builder.include(&common_dir).include(target_dir).files(
-            scheme_files
-                .into_iter()
-                .map(|p| p.unwrap().to_string_lossy().into_owned()),
-        );
+        builder
+            .include(&internals_include_dir)
+            .include(&common_dir)
+            .include(target_dir)
+            .files(
+                scheme_files
+                    .into_iter()
+                    .map(|p| p.unwrap().to_string_lossy().into_owned()),
+            );
         builder.compile("lightsaber_clean");
     }
 
@@ -51,11 +63,15 @@ fn main() {
         let mut builder = cc::Build::new();
         let target_dir: PathBuf = ["pqclean", "crypto_kem", "saber", "clean"].iter().collect();
         let scheme_files = glob::glob(target_dir.join("*.c").to_str().unwrap()).unwrap();
-        builder.include(&common_dir).include(target_dir).files(
-            scheme_files
-                .into_iter()
-                .map(|p| p.unwrap().to_string_lossy().into_owned()),
-        );
+        builder
+            .include(&internals_include_dir)
+            .include(&common_dir)
+            .include(target_dir)
+            .files(
+                scheme_files
+                    .into_iter()
+                    .map(|p| p.unwrap().to_string_lossy().into_owned()),
+            );
         builder.compile("saber_clean");
     }
 }
@@ -8,7 +8,7 @@
 //!  * saber
 // This file has been generated from PQClean.
 // Find the templates in pqcrypto-template
-use libc::c_int;
+use core::ffi::c_int;
 
 pub const PQCLEAN_FIRESABER_CLEAN_CRYPTO_SECRETKEYBYTES: usize = 3040;
 pub const PQCLEAN_FIRESABER_CLEAN_CRYPTO_PUBLICKEYBYTES: usize = 1312;
@@ -10,8 +10,11 @@
 //! [pqc]: https://github.com/pqclean/pqclean/
 //!
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::len_without_is_empty)]
 
+extern crate alloc;
+
 pub mod ffi;
 
 pub mod firesaber;
@@ -16,12 +16,21 @@
 // This file is generated.
 
 use crate::ffi;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use pqcrypto_internals::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use pqcrypto_traits::encoding;
 use pqcrypto_traits::kem as primitive;
 use pqcrypto_traits::{Error, Result};
 
+/// The length of the seeds accepted by the deterministic functions
+pub use pqcrypto_internals::SEED_BYTES;
+
 macro_rules! simple_struct {
     ($type: ident, $size: expr) => {
-        #[derive(Clone, Copy)]
+        #[derive(Clone)]
         pub struct $type([u8; $size]);
 
         impl $type {
@@ -51,21 +60,67 @@ macro_rules! simple_struct {
                         expected: $size,
                     })
                 } else {
-                    let mut array = [0u8; $size];
-                    array.copy_from_slice(bytes);
-                    Ok($type(array))
+                    // copy straight into the object so no unwiped temporary is left behind
+                    let mut object = $type::new();
+                    object.0.copy_from_slice(bytes);
+                    Ok(object)
                 }
             }
         }
 
+        impl ConstantTimeEq for $type {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.0[..].ct_eq(&other.0[..])
+            }
+        }
+
         impl PartialEq for $type {
-            /// By no means constant time comparison
+            /// Constant time comparison
             fn eq(&self, other: &Self) -> bool {
-                self.0
-                    .iter()
-                    .zip(other.0.iter())
-                    .try_for_each(|(a, b)| if a == b { Ok(()) } else { Err(()) })
-                    .is_ok()
+                self.ct_eq(other).into()
+            }
+        }
+
+        impl Eq for $type {}
+
+        impl Zeroize for $type {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl Drop for $type {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+
+        impl ZeroizeOnDrop for $type {}
+
+        impl core::fmt::Debug for $type {
+            /// Never prints the contents, which may be secret
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{}(<{} bytes redacted>)", stringify!($type), $size)
+            }
+        }
+    };
+}
+
+/// Serializes public values with the helpers in ``pqcrypto_traits::serialization``.
+/// Secret keys and shared secrets are deliberately left out.
+#[cfg(feature = "serialization")]
+macro_rules! serde_struct {
+    ($type: ident) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+                pqcrypto_traits::serialization::serialize_bytes(&self.0, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+                let bytes = pqcrypto_traits::serialization::deserialize_bytes(deserializer)?;
+                <$type as primitive::$type>::from_bytes(&bytes).map_err(serde::de::Error::custom)
             }
         }
     };
@@ -85,6 +140,34 @@ simple_struct!(
 );
 simple_struct!(SharedSecret, ffi::PQCLEAN_LIGHTSABER_CLEAN_CRYPTO_BYTES);
 
+#[cfg(feature = "serialization")]
+serde_struct!(PublicKey);
+#[cfg(feature = "serialization")]
+serde_struct!(Ciphertext);
+
+/// The object identifier used in the DER and PEM encodings of this scheme's keys
+pub const OID: &[u32] = &[1, 3, 6, 1, 4, 1, 22554, 5, 7, 1];
+
+impl encoding::PublicKeyEncoding for PublicKey {
+    fn to_public_key_der(&self) -> Vec<u8> {
+        encoding::encode_spki(OID, &self.0)
+    }
+
+    fn from_public_key_der(der: &[u8]) -> Result<Self> {
+        <PublicKey as primitive::PublicKey>::from_bytes(encoding::decode_spki(OID, der)?)
+    }
+}
+
+impl encoding::SecretKeyEncoding for SecretKey {
+    fn to_pkcs8_der(&self) -> Zeroizing<Vec<u8>> {
+        encoding::encode_pkcs8(OID, &self.0)
+    }
+
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        <SecretKey as primitive::SecretKey>::from_bytes(encoding::decode_pkcs8(OID, der)?)
+    }
+}
+
 /// Get the number of bytes for a public key
 pub const fn public_key_bytes() -> usize {
     ffi::PQCLEAN_LIGHTSABER_CLEAN_CRYPTO_PUBLICKEYBYTES
@@ -110,6 +193,18 @@ pub fn keypair() -> (PublicKey, SecretKey) {
     keypair_portable()
 }
 
+/// Deterministically generate a lightsaber keypair, drawing all of its randomness from a DRBG seeded with `seed`
+#[cfg(feature = "std")]
+pub fn keypair_from_seed(seed: &[u8; SEED_BYTES]) -> (PublicKey, SecretKey) {
+    pqcrypto_internals::with_seeded_randombytes(seed, keypair)
+}
+
+/// Generate a lightsaber keypair, drawing all of its randomness from `rng` instead of the operating system
+#[cfg(feature = "std")]
+pub fn keypair_with_rng<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> (PublicKey, SecretKey) {
+    pqcrypto_internals::with_rng(rng, keypair)
+}
+
 #[inline]
 fn keypair_portable() -> (PublicKey, SecretKey) {
     let mut pk = PublicKey::new();
@@ -128,6 +223,19 @@ pub fn encapsulate(pk: &PublicKey) -> (SharedSecret, Ciphertext) {
     encapsulate_portable(pk)
 }
 
+/// Deterministically encapsulate to a lightsaber public key, drawing the randomness from a DRBG seeded with `coins`.
+/// Reusing `coins` for the same public key reproduces the same shared secret
+#[cfg(feature = "std")]
+pub fn encapsulate_deterministic(pk: &PublicKey, coins: &[u8; SEED_BYTES]) -> (SharedSecret, Ciphertext) {
+    pqcrypto_internals::with_seeded_randombytes(coins, || encapsulate(pk))
+}
+
+/// Encapsulate to a lightsaber public key, drawing the randomness from `rng` instead of the operating system
+#[cfg(feature = "std")]
+pub fn encapsulate_with_rng<R: RngCore + CryptoRng + ?Sized>(pk: &PublicKey, rng: &mut R) -> (SharedSecret, Ciphertext) {
+    pqcrypto_internals::with_rng(rng, || encapsulate(pk))
+}
+
 #[inline]
 fn encapsulate_portable(pk: &PublicKey) -> (SharedSecret, Ciphertext) {
     let mut ss = SharedSecret::new();
@@ -168,6 +276,37 @@ fn decapsulate_portable(ct: &Ciphertext, sk: &SecretKey) -> SharedSecret {
     ss
 }
 
+/// lightsaber as a [primitive::Kem], for code that is generic over the scheme
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lightsaber;
+
+impl primitive::Kem for Lightsaber {
+    const PUBLIC_KEY_BYTES: usize = public_key_bytes();
+    const SECRET_KEY_BYTES: usize = secret_key_bytes();
+    const CIPHERTEXT_BYTES: usize = ciphertext_bytes();
+    const SHARED_SECRET_BYTES: usize = shared_secret_bytes();
+
+    type PublicKey = PublicKey;
+    type SecretKey = SecretKey;
+    type Ciphertext = Ciphertext;
+    type SharedSecret = SharedSecret;
+
+    #[inline]
+    fn keypair() -> (PublicKey, SecretKey) {
+        keypair()
+    }
+
+    #[inline]
+    fn encapsulate(pk: &PublicKey) -> (SharedSecret, Ciphertext) {
+        encapsulate(pk)
+    }
+
+    #[inline]
+    fn decapsulate(ct: &Ciphertext, sk: &SecretKey) -> SharedSecret {
+        decapsulate(ct, sk)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -179,4 +318,151 @@ mod test {
         let ss2 = decapsulate(&ct, &sk);
         assert_eq!(&ss1.0[..], &ss2.0[..], "Difference in shared secrets!");
     }
+
+    #[test]
+    pub fn test_kem_from_seed() {
+        let (pk, sk) = keypair_from_seed(&[1u8; SEED_BYTES]);
+        let (pk2, sk2) = keypair_from_seed(&[1u8; SEED_BYTES]);
+        assert!(pk == pk2 && sk == sk2);
+        assert!(keypair_from_seed(&[2u8; SEED_BYTES]).0 != pk);
+
+        let (ss1, ct) = encapsulate_deterministic(&pk, &[3u8; SEED_BYTES]);
+        let (ss2, ct2) = encapsulate_deterministic(&pk, &[3u8; SEED_BYTES]);
+        assert!(ss1 == ss2 && ct == ct2);
+        assert!(decapsulate(&ct, &sk) == ss1);
+        assert!(keypair().0 != pk);
+    }
+
+    #[test]
+    pub fn test_kem_trait() {
+        fn roundtrip<K: primitive::Kem>() -> bool {
+            let (pk, sk) = K::keypair();
+            let (ss1, ct) = K::encapsulate(&pk);
+            let ss2 = K::decapsulate(&ct, &sk);
+            primitive::PublicKey::as_bytes(&pk).len() == K::PUBLIC_KEY_BYTES
+                && primitive::Ciphertext::as_bytes(&ct).len() == K::CIPHERTEXT_BYTES
+                && primitive::SharedSecret::as_bytes(&ss1) == primitive::SharedSecret::as_bytes(&ss2)
+        }
+
+        assert!(roundtrip::<Lightsaber>());
+    }
+
+    /// Fails every draw, or panics if `panics` is set
+    #[cfg(feature = "std")]
+    struct BrokenRng {
+        panics: bool,
+    }
+
+    #[cfg(feature = "std")]
+    impl RngCore for BrokenRng {
+        fn next_u32(&mut self) -> u32 {
+            pqcrypto_internals::rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            pqcrypto_internals::rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.try_fill_bytes(dest).unwrap()
+        }
+
+        fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> core::result::Result<(), pqcrypto_internals::rand_core::Error> {
+            if self.panics {
+                panic!("broken RNG");
+            }
+
+            let code = core::num::NonZeroU32::new(pqcrypto_internals::rand_core::Error::CUSTOM_START).unwrap();
+            Err(code.into())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl CryptoRng for BrokenRng {}
+
+    /// Key generation with a failing or panicking RNG must not return a (predictable) key. It aborts the process, so
+    /// this test runs itself again in a child process for each case
+    #[cfg(feature = "std")]
+    #[test]
+    pub fn test_broken_rng_aborts() {
+        const MODE: &str = "PQCRYPTO_TEST_BROKEN_RNG";
+        if let Ok(mode) = std::env::var(MODE) {
+            let _ = keypair_with_rng(&mut BrokenRng { panics: mode == "panic" });
+            println!("keypair returned");
+            return;
+        }
+
+        let name = format!("{}::test_broken_rng_aborts", module_path!().splitn(2, "::").nth(1).unwrap());
+        for mode in &["error", "panic"] {
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(&["--exact", &name, "--nocapture", "--test-threads=1"])
+                .env(MODE, mode)
+                .output()
+                .unwrap();
+            assert!(!output.status.success());
+            assert!(!String::from_utf8_lossy(&output.stdout).contains("keypair returned"));
+            #[cfg(unix)]
+            assert_eq!(std::os::unix::process::ExitStatusExt::signal(&output.status), Some(6), "expected SIGABRT");
+        }
+    }
+
+    /// The SHA-256 of PQClean's NIST known-answer test output for this scheme, from its META.yml
+    const NISTKAT_SHA256: &str = "dc2233ae221cfabbb1db5ab1a76c93967d37de9f87a8092561f95ab28eff6061";
+
+    /// Regenerates PQClean's ``nistkat`` output with the NIST CTR_DRBG and compares its digest
+    #[test]
+    pub fn test_nistkat() {
+        extern "C" {
+            // from PQClean's common/sha2.c, which is linked into this crate
+            fn sha256(out: *mut u8, input: *const u8, inlen: usize);
+        }
+
+        fn line(rsp: &mut String, label: &str, bytes: &[u8]) {
+            rsp.push_str(label);
+            rsp.push_str(" = ");
+            bytes.iter().for_each(|byte| rsp.push_str(&format!("{:02X}", byte)));
+            rsp.push('\n');
+        }
+
+        let draw = |output: &mut [u8]| assert_eq!(unsafe { pqcrypto_internals::PQCRYPTO_RUST_randombytes(output.as_mut_ptr(), output.len()) }, 0);
+        let mut entropy = [0u8; SEED_BYTES];
+        entropy.iter_mut().enumerate().for_each(|(i, byte)| *byte = i as u8);
+
+        let mut rsp = String::from("count = 0\n");
+        let mut seed = [0u8; SEED_BYTES];
+        pqcrypto_internals::with_seeded_randombytes(&entropy, || draw(&mut seed));
+        line(&mut rsp, "seed", &seed);
+
+        let (pk, sk, ss, ct) = pqcrypto_internals::with_seeded_randombytes(&seed, || {
+            let (pk, sk) = keypair();
+            let (ss, ct) = encapsulate(&pk);
+            (pk, sk, ss, ct)
+        });
+        line(&mut rsp, "pk", &pk.0);
+        line(&mut rsp, "sk", &sk.0);
+        line(&mut rsp, "ct", &ct.0);
+        line(&mut rsp, "ss", &ss.0);
+        assert!(decapsulate(&ct, &sk) == ss);
+        let mut digest = [0u8; 32];
+        unsafe { sha256(digest.as_mut_ptr(), rsp.as_ptr(), rsp.len()) };
+        let digest: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        assert_eq!(digest, NISTKAT_SHA256);
+    }
+
+    #[test]
+    pub fn test_key_encoding() {
+        use pqcrypto_traits::encoding::{PublicKeyEncoding, SecretKeyEncoding};
+
+        let (pk, sk) = keypair();
+        assert!(PublicKey::from_public_key_pem(&pk.to_public_key_pem()).unwrap() == pk);
+        assert!(SecretKey::from_pkcs8_pem(&sk.to_pkcs8_pem()).unwrap() == sk);
+
+        // the structure, OID and key length are all checked
+        let der = pk.to_public_key_der();
+        assert!(PublicKey::from_public_key_der(&sk.to_pkcs8_der()).is_err());
+        assert!(PublicKey::from_public_key_der(&der[..der.len() - 1]).is_err());
+        assert!(PublicKey::from_public_key_der(&encoding::encode_spki(OID, &pk.0[1..])).is_err());
+        assert!(PublicKey::from_public_key_der(&encoding::encode_spki(&[1, 3, 6, 1, 4, 1, 22554, 0], &pk.0)).is_err());
+        assert!(SecretKey::from_pkcs8_pem(&pk.to_public_key_pem()).is_err());
+    }
 }
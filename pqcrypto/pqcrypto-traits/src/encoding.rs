@@ -0,0 +1,266 @@
+use crate::{Error, Result};
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zeroize::Zeroizing;
+
+/// The private enterprise arc of the Open Quantum Safe project, under which the scheme OIDs are allocated
+pub const OQS_ARC: &[u32] = &[1, 3, 6, 1, 4, 1, 22554];
+
+/// The PEM label of a DER-encoded SubjectPublicKeyInfo
+pub const PUBLIC_KEY_PEM_LABEL: &str = "PUBLIC KEY";
+/// The PEM label of a DER-encoded, unencrypted PKCS#8 private key
+pub const PRIVATE_KEY_PEM_LABEL: &str = "PRIVATE KEY";
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OBJECT_IDENTIFIER: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+/// PEM bodies are wrapped at 64 characters, as OpenSSL does
+const PEM_LINE_LENGTH: usize = 64;
+
+/// A public key that can be encoded as an X.509 SubjectPublicKeyInfo
+pub trait PublicKeyEncoding: Sized {
+    /// Encodes this key as a DER SubjectPublicKeyInfo
+    fn to_public_key_der(&self) -> Vec<u8>;
+
+    /// Decodes a DER SubjectPublicKeyInfo, which must carry this scheme's OID and a key of the exact length
+    fn from_public_key_der(der: &[u8]) -> Result<Self>;
+
+    /// Encodes this key as a PEM "PUBLIC KEY"
+    fn to_public_key_pem(&self) -> String {
+        pem_encode(PUBLIC_KEY_PEM_LABEL, &self.to_public_key_der())
+    }
+
+    /// Decodes a PEM "PUBLIC KEY"
+    fn from_public_key_pem(pem: &str) -> Result<Self> {
+        Self::from_public_key_der(&pem_decode(PUBLIC_KEY_PEM_LABEL, pem)?)
+    }
+}
+
+/// A secret key that can be encoded as an unencrypted PKCS#8 private key. The encodings hold the raw secret key, so
+/// they are wiped on drop
+pub trait SecretKeyEncoding: Sized {
+    /// Encodes this key as DER PKCS#8
+    fn to_pkcs8_der(&self) -> Zeroizing<Vec<u8>>;
+
+    /// Decodes DER PKCS#8, which must carry this scheme's OID and a key of the exact length
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self>;
+
+    /// Encodes this key as a PEM "PRIVATE KEY"
+    fn to_pkcs8_pem(&self) -> Zeroizing<String> {
+        Zeroizing::new(pem_encode(PRIVATE_KEY_PEM_LABEL, &self.to_pkcs8_der()))
+    }
+
+    /// Decodes a PEM "PRIVATE KEY"
+    fn from_pkcs8_pem(pem: &str) -> Result<Self> {
+        Self::from_pkcs8_der(&pem_decode(PRIVATE_KEY_PEM_LABEL, pem)?)
+    }
+}
+
+/// Encodes a SubjectPublicKeyInfo. As with the OQS algorithms, the algorithm parameters are absent
+pub fn encode_spki(oid: &[u32], public_key: &[u8]) -> Vec<u8> {
+    let algorithm = algorithm_identifier(oid);
+    let key_length = 1 + public_key.len();
+    let inner_length = algorithm.len() + header_length(key_length) + key_length;
+
+    let mut output = Vec::with_capacity(header_length(inner_length) + inner_length);
+    push_header(&mut output, TAG_SEQUENCE, inner_length);
+    output.extend_from_slice(&algorithm);
+    push_header(&mut output, TAG_BIT_STRING, key_length);
+    // no unused bits
+    output.push(0);
+    output.extend_from_slice(public_key);
+    output
+}
+
+/// Returns the raw key inside a SubjectPublicKeyInfo, checking that it was issued for `oid`
+pub fn decode_spki<'a>(oid: &[u32], der: &'a [u8]) -> Result<&'a [u8]> {
+    let mut outer = Reader(der);
+    let mut inner = Reader(outer.read(TAG_SEQUENCE)?);
+    outer.finish()?;
+
+    read_algorithm_identifier(&mut inner, oid)?;
+    let key = inner.read(TAG_BIT_STRING)?;
+    inner.finish()?;
+
+    match key.split_first() {
+        Some((0, key)) => Ok(key),
+        _ => Err(Error::BadEncoding { reason: "public key bit string is not byte-aligned" })
+    }
+}
+
+/// Encodes a version 1 PKCS#8 private key. The output is sized up front so no unwiped copy is left behind by a reallocation
+pub fn encode_pkcs8(oid: &[u32], secret_key: &[u8]) -> Zeroizing<Vec<u8>> {
+    let algorithm = algorithm_identifier(oid);
+    let inner_length = 3 + algorithm.len() + header_length(secret_key.len()) + secret_key.len();
+
+    let mut output = Zeroizing::new(Vec::with_capacity(header_length(inner_length) + inner_length));
+    push_header(&mut output, TAG_SEQUENCE, inner_length);
+    output.extend_from_slice(&[TAG_INTEGER, 1, 0]);
+    output.extend_from_slice(&algorithm);
+    push_header(&mut output, TAG_OCTET_STRING, secret_key.len());
+    output.extend_from_slice(secret_key);
+    output
+}
+
+/// Returns the raw key inside a version 1 PKCS#8 private key, checking that it was issued for `oid`. Attributes are not accepted
+pub fn decode_pkcs8<'a>(oid: &[u32], der: &'a [u8]) -> Result<&'a [u8]> {
+    let mut outer = Reader(der);
+    let mut inner = Reader(outer.read(TAG_SEQUENCE)?);
+    outer.finish()?;
+
+    if inner.read(TAG_INTEGER)? != [0] {
+        return Err(Error::BadEncoding { reason: "unsupported PKCS#8 version" });
+    }
+
+    read_algorithm_identifier(&mut inner, oid)?;
+    let key = inner.read(TAG_OCTET_STRING)?;
+    inner.finish()?;
+    Ok(key)
+}
+
+/// Wraps `der` in PEM armor with the given label
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let body = Zeroizing::new(STANDARD.encode(der));
+    let lines = body.len().div_ceil(PEM_LINE_LENGTH);
+
+    let mut output = String::with_capacity(2 * (label.len() + 16) + body.len() + lines);
+    output.push_str("-----BEGIN ");
+    output.push_str(label);
+    output.push_str("-----\n");
+    for line in body.as_bytes().chunks(PEM_LINE_LENGTH) {
+        // base64 output is always ASCII
+        output.extend(line.iter().map(|&byte| byte as char));
+        output.push('\n');
+    }
+    output.push_str("-----END ");
+    output.push_str(label);
+    output.push_str("-----\n");
+    output
+}
+
+/// Removes the PEM armor from `pem`, which must carry exactly the given label
+pub fn pem_decode(label: &str, pem: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let body = pem.trim()
+        .strip_prefix("-----BEGIN ")
+        .and_then(|rest| rest.strip_prefix(label))
+        .and_then(|rest| rest.strip_prefix("-----"))
+        .and_then(|rest| rest.strip_suffix("-----"))
+        .and_then(|rest| rest.strip_suffix(label))
+        .and_then(|rest| rest.strip_suffix("-----END "))
+        .ok_or(Error::BadEncoding { reason: "missing or mismatched PEM label" })?;
+
+    let body: Zeroizing<String> = Zeroizing::new(body.chars().filter(|c| !c.is_ascii_whitespace()).collect());
+    STANDARD.decode(body.as_bytes())
+        .map(Zeroizing::new)
+        .map_err(|_| Error::BadEncoding { reason: "invalid base64 in PEM body" })
+}
+
+fn algorithm_identifier(oid: &[u32]) -> Vec<u8> {
+    let oid = encode_oid(oid);
+    let mut output = Vec::with_capacity(2 * header_length(oid.len()) + oid.len());
+    push_header(&mut output, TAG_SEQUENCE, header_length(oid.len()) + oid.len());
+    push_header(&mut output, TAG_OBJECT_IDENTIFIER, oid.len());
+    output.extend_from_slice(&oid);
+    output
+}
+
+fn read_algorithm_identifier(reader: &mut Reader, oid: &[u32]) -> Result<()> {
+    let mut algorithm = Reader(reader.read(TAG_SEQUENCE)?);
+    if algorithm.read(TAG_OBJECT_IDENTIFIER)? != encode_oid(oid).as_slice() {
+        return Err(Error::BadEncoding { reason: "unexpected algorithm identifier" });
+    }
+
+    // parameters must be absent
+    algorithm.finish()
+}
+
+fn encode_oid(oid: &[u32]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let first = oid[0] * 40 + oid[1];
+    for &arc in core::iter::once(&first).chain(&oid[2..]) {
+        let mut groups = 1;
+        while groups < 5 && arc >> (7 * groups) != 0 {
+            groups += 1;
+        }
+
+        for group in (0..groups).rev() {
+            let continuation = if group == 0 { 0 } else { 0x80 };
+            output.push(continuation | ((arc >> (7 * group)) & 0x7F) as u8);
+        }
+    }
+
+    output
+}
+
+fn header_length(length: usize) -> usize {
+    1 + if length < 0x80 { 1 } else { 1 + length_bytes(length) }
+}
+
+fn length_bytes(length: usize) -> usize {
+    core::mem::size_of::<usize>() - (length.leading_zeros() as usize / 8)
+}
+
+fn push_header(output: &mut Vec<u8>, tag: u8, length: usize) {
+    output.push(tag);
+    if length < 0x80 {
+        output.push(length as u8);
+    } else {
+        let count = length_bytes(length);
+        output.push(0x80 | count as u8);
+        output.extend_from_slice(&length.to_be_bytes()[core::mem::size_of::<usize>() - count..]);
+    }
+}
+
+/// A cursor over DER input that only accepts definite, minimally encoded lengths
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        if count > self.0.len() {
+            return Err(Error::BadEncoding { reason: "truncated DER" });
+        }
+
+        let (taken, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn read(&mut self, tag: u8) -> Result<&'a [u8]> {
+        if self.take(1)? != [tag] {
+            return Err(Error::BadEncoding { reason: "unexpected DER tag" });
+        }
+
+        let first = self.take(1)?[0];
+        let length = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > core::mem::size_of::<usize>() {
+                return Err(Error::BadEncoding { reason: "unsupported DER length" });
+            }
+
+            let bytes = self.take(count)?;
+            let length = bytes.iter().fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            if bytes[0] == 0 || length < 0x80 {
+                return Err(Error::BadEncoding { reason: "non-minimal DER length" });
+            }
+
+            length
+        };
+
+        self.take(length)
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::BadEncoding { reason: "trailing data after DER value" })
+        }
+    }
+}
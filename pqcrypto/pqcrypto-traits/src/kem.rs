@@ -22,7 +22,7 @@ pub trait Ciphertext {
     fn as_bytes(&self) -> &[u8];
     fn from_bytes(bytes: &[u8]) -> Result<Self>
     where
-        Self: Sized + Clone + Copy;
+        Self: Sized + Clone;
 }
 
 /// The shared secret that should be agreed on.
@@ -30,5 +30,33 @@ pub trait SharedSecret {
     fn as_bytes(&self) -> &[u8];
     fn from_bytes(bytes: &[u8]) -> Result<Self>
     where
-        Self: Sized + Clone + Copy;
+        Self: Sized + Clone;
+}
+
+/// A Key-Encapsulation Mechanism, for code that is generic over the scheme.
+///
+/// Every scheme module provides a unit struct implementing it, e.g. ``firesaber::Firesaber``.
+pub trait Kem {
+    /// The length of a public key
+    const PUBLIC_KEY_BYTES: usize;
+    /// The length of a secret key
+    const SECRET_KEY_BYTES: usize;
+    /// The length of the encapsulated ciphertext
+    const CIPHERTEXT_BYTES: usize;
+    /// The length of the shared secret
+    const SHARED_SECRET_BYTES: usize;
+
+    type PublicKey: PublicKey + Clone + Send + Sync;
+    type SecretKey: SecretKey + Clone + Send + Sync;
+    type Ciphertext: Ciphertext + Clone + Send + Sync;
+    type SharedSecret: SharedSecret + Clone + Send + Sync;
+
+    /// Generate a keypair
+    fn keypair() -> (Self::PublicKey, Self::SecretKey);
+
+    /// Encapsulate a fresh shared secret to `pk`
+    fn encapsulate(pk: &Self::PublicKey) -> (Self::SharedSecret, Self::Ciphertext);
+
+    /// Decapsulate the received ciphertext
+    fn decapsulate(ct: &Self::Ciphertext, sk: &Self::SecretKey) -> Self::SharedSecret;
 }
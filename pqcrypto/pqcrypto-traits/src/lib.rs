@@ -1,7 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 /// Supporting Traits for the pqcrypto crates.
 
 /// Convenience wrapper for Result
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Errors that may arise when constructing keys or signatures.
 #[derive(Clone, Copy, Debug)]
@@ -11,12 +15,15 @@ pub enum Error {
         actual: usize,
         expected: usize,
     },
+    BadEncoding {
+        reason: &'static str,
+    },
     #[doc(hidden)]
     __NonExhaustive,
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             Error::BadLength {
                 name,
@@ -27,12 +34,21 @@ impl std::fmt::Display for Error {
                 "error: {} expected {} bytes, got {}",
                 name, actual, expected
             ),
+            Error::BadEncoding { reason } => write!(f, "error: malformed encoding: {}", reason),
             Error::__NonExhaustive => unreachable!("Should never be constructed"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
 pub mod kem;
 pub mod sign;
+
+/// DER (SubjectPublicKeyInfo and PKCS#8) and PEM encodings of keys
+pub mod encoding;
+
+/// Helpers for implementing serde on key and ciphertext types
+#[cfg(feature = "serialization")]
+pub mod serialization;
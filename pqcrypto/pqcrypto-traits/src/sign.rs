@@ -1,5 +1,6 @@
 /// Traits for signature schemes
 use crate::Result;
+use alloc::vec::Vec;
 
 /// A public key for a signature scheme
 pub trait PublicKey {
@@ -56,8 +57,8 @@ pub enum VerificationError {
     __NonExhaustive,
 }
 
-impl std::fmt::Display for VerificationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+impl core::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
         match self {
             VerificationError::InvalidSignature => write!(f, "error: verification failed"),
             VerificationError::UnknownVerificationError => write!(f, "unknown error"),
@@ -68,4 +69,41 @@ impl std::fmt::Display for VerificationError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for VerificationError {}
+
+/// A signature scheme, for code that is generic over the scheme.
+///
+/// Every scheme module provides a unit struct implementing it, e.g. ``dilithium2::Dilithium2``.
+pub trait Signer {
+    /// The length of a public key
+    const PUBLIC_KEY_BYTES: usize;
+    /// The length of a secret key
+    const SECRET_KEY_BYTES: usize;
+    /// The maximum length of a detached signature
+    const SIGNATURE_BYTES: usize;
+
+    type PublicKey: PublicKey + Clone + Send + Sync;
+    type SecretKey: SecretKey + Clone + Send + Sync;
+    type SignedMessage: SignedMessage + Clone + Send + Sync;
+    type DetachedSignature: DetachedSignature + Clone + Send + Sync;
+
+    /// Generate a keypair
+    fn keypair() -> (Self::PublicKey, Self::SecretKey);
+
+    /// Sign the message and return the signed message
+    fn sign(msg: &[u8], sk: &Self::SecretKey) -> Self::SignedMessage;
+
+    /// Verify the signed message and return the message it contains
+    fn open(sm: &Self::SignedMessage, pk: &Self::PublicKey) -> core::result::Result<Vec<u8>, VerificationError>;
+
+    /// Sign the message and return only the signature
+    fn detached_sign(msg: &[u8], sk: &Self::SecretKey) -> Self::DetachedSignature;
+
+    /// Verify a detached signature on `msg`
+    fn verify_detached_signature(
+        sig: &Self::DetachedSignature,
+        msg: &[u8],
+        pk: &Self::PublicKey,
+    ) -> core::result::Result<(), VerificationError>;
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 /// Post-Quantum cryptographic primitives
 ///
 /// Packages the [PQClean][pqclean] project as Rust crates
@@ -6,6 +8,7 @@
 pub use pqcrypto_traits as traits;
 
 pub mod prelude {
+    pub use pqcrypto_traits::encoding::{PublicKeyEncoding as _, SecretKeyEncoding as _};
     pub use pqcrypto_traits::kem::{
         Ciphertext as _, PublicKey as _, SecretKey as _, SharedSecret as _,
     };
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use core::ops::Range;
 #[cfg(feature = "chacha20")]
 use chacha20poly1305::aead::{Buffer, Error};
 
@@ -47,4 +47,61 @@ impl AsRef<[u8]> for InPlaceBytesMut<'_> {
     fn as_ref(&self) -> &[u8] {
         &self.inner[self.window.clone()]
     }
-}
\ No newline at end of file
+}
+
+/// A fixed-capacity buffer over a mutable slice. The first `len` bytes hold the data, and the
+/// remaining bytes are tail room. Unlike [InPlaceBytesMut], this never allocates: extending past
+/// the end of the slice returns an error
+pub struct InPlaceSlice<'a> {
+    inner: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> InPlaceSlice<'a> {
+    /// `len`: the number of bytes already in use. Returns None if `len` exceeds the slice length
+    pub fn new(inner: &'a mut [u8], len: usize) -> Option<InPlaceSlice<'a>> {
+        if len > inner.len() {
+            None
+        } else {
+            Some(Self { inner, len })
+        }
+    }
+
+    /// The total number of bytes this buffer can hold
+    pub fn capacity(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl Buffer for InPlaceSlice<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn extend_from_slice(&mut self, other: &[u8]) -> Result<(), Error> {
+        let end = self.len.checked_add(other.len()).ok_or(Error)?;
+        if end > self.inner.len() {
+            return Err(Error);
+        }
+
+        self.inner[self.len..end].copy_from_slice(other);
+        self.len = end;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.len = core::cmp::min(self.len, len);
+    }
+}
+
+impl AsMut<[u8]> for InPlaceSlice<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.inner[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for InPlaceSlice<'_> {
+    fn as_ref(&self) -> &[u8] {
+        &self.inner[..self.len]
+    }
+}
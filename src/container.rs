@@ -0,0 +1,689 @@
+use crate::{PQNode, PostQuantumType, Tag, MAX_EXPORT_LENGTH, PACKET_OVERHEAD_BYTES, nonce_from_slice, hex_string};
+use crate::bytes_in_place::{InPlaceBytesMut, InPlaceSlice};
+use crate::export::PostQuantumExport;
+use crate::ez_error::EzError;
+use crate::replay_attack_container::ordered::AntiReplayAttackContainerOrdered;
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use crate::{CryptoRngCore, SEED_LENGTH_BYTES};
+#[cfg(feature = "std")]
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+use alloc::vec::Vec;
+use alloc::vec;
+use bytes::{BytesMut, BufMut};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+use pqcrypto::traits::kem::{PublicKey as _, SecretKey as _, Ciphertext as _, SharedSecret as _};
+#[cfg(feature = "chacha20")]
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{NewAead, Aead as _, AeadInPlace, Buffer, generic_array::{GenericArray, typenum::{U16, U32}}}};
+#[cfg(not(feature = "chacha20"))]
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{NewAead, Aead as _, AeadInPlace, Buffer, generic_array::{GenericArray, typenum::{U16, U32}}}};
+
+pub use pqcrypto::traits::kem::Kem;
+
+/// A [Kem] listed in [crate::algorithm_dictionary], and so usable by [Container]
+pub trait Algorithm: Kem + Send + Sync + 'static {
+    /// The byte identifying this KEM in [crate::algorithm_dictionary]
+    const ALGORITHM: u8;
+
+    /// Generates a keypair, drawing randomness from `rng`
+    #[cfg(feature = "std")]
+    fn keypair_with_rng(rng: &mut dyn CryptoRngCore) -> (Self::PublicKey, Self::SecretKey);
+    /// Derives a keypair deterministically from `seed`
+    #[cfg(feature = "std")]
+    fn keypair_from_seed(seed: &[u8; SEED_LENGTH_BYTES]) -> (Self::PublicKey, Self::SecretKey);
+}
+
+/// An AEAD usable by [Container]: a 256-bit key and a 16-byte tag
+pub trait Aead: NewAead<KeySize = U32> + AeadInPlace<TagSize = U16> + Send + Sync {
+    /// Wipes the expanded key. Called when the container is dropped
+    fn wipe(&mut self);
+}
+
+impl Aead for AeadKey {
+    fn wipe(&mut self) {
+        crate::wipe_aead_key(self)
+    }
+}
+
+macro_rules! impl_algorithm {
+    ($type:ident, $name:ident, $algorithm:ident) => {
+        pub use pqcrypto::kem::$name::$type;
+
+        impl Algorithm for $type {
+            const ALGORITHM: u8 = crate::algorithm_dictionary::$algorithm;
+
+            #[cfg(feature = "std")]
+            fn keypair_with_rng(rng: &mut dyn CryptoRngCore) -> (Self::PublicKey, Self::SecretKey) {
+                pqcrypto::kem::$name::keypair_with_rng(rng)
+            }
+
+            #[cfg(feature = "std")]
+            fn keypair_from_seed(seed: &[u8; SEED_LENGTH_BYTES]) -> (Self::PublicKey, Self::SecretKey) {
+                pqcrypto::kem::$name::keypair_from_seed(seed)
+            }
+        }
+    };
+}
+
+/*
+impl_algorithm!(Babybear, babybear, BABYBEAR);
+impl_algorithm!(Babybearephem, babybearephem, BABYBEAREPHEM);
+*/
+impl_algorithm!(Firesaber, firesaber, FIRESABER);
+/*
+impl_algorithm!(Frodokem640aes, frodokem640aes, FRODOKEM640AES);
+impl_algorithm!(Frodokem640shake, frodokem640shake, FRODOKEM640SHAKE);
+impl_algorithm!(Frodokem976aes, frodokem976aes, FRODOKEM976AES);
+impl_algorithm!(Frodokem976shake, frodokem976shake, FRODOKEM976SHAKE);
+impl_algorithm!(Frodokem1344aes, frodokem1344aes, FRODOKEM1344AES);
+impl_algorithm!(Frodokem1344shake, frodokem1344shake, FRODOKEM1344SHAKE);
+
+impl_algorithm!(Kyber512, kyber512, KYBER512);
+impl_algorithm!(Kyber768, kyber768, KYBER768);
+impl_algorithm!(Kyber1024, kyber1024, KYBER1024);
+impl_algorithm!(Kyber51290s, kyber51290s, KYBER51290S);
+impl_algorithm!(Kyber76890s, kyber76890s, KYBER76890S);
+impl_algorithm!(Kyber102490s, kyber102490s, KYBER102490S);
+
+impl_algorithm!(Ledakemlt12, ledakemlt12, LEDAKEMLT12);
+impl_algorithm!(Ledakemlt32, ledakemlt32, LEDAKEMLT32);
+impl_algorithm!(Ledakemlt52, ledakemlt52, LEDAKEMLT52);
+
+impl_algorithm!(Lightsaber, lightsaber, LIGHTSABER);
+
+impl_algorithm!(Mamabear, mamabear, MAMABEAR);
+impl_algorithm!(Mamabearephem, mamabearephem, MAMABEAREPHEM);
+
+impl_algorithm!(Mceliece348864, mceliece348864, MCELIECE348864);
+impl_algorithm!(Mceliece348864f, mceliece348864f, MCELIECE348864F);
+impl_algorithm!(Mceliece460896, mceliece460896, MCELIECE460896);
+impl_algorithm!(Mceliece460896f, mceliece460896f, MCELIECE460896F);
+impl_algorithm!(Mceliece6688128, mceliece6688128, MCELIECE6688128);
+impl_algorithm!(Mceliece6688128f, mceliece6688128f, MCELIECE6688128F);
+impl_algorithm!(Mceliece6960119, mceliece6960119, MCELIECE6960119);
+impl_algorithm!(Mceliece6960119f, mceliece6960119f, MCELIECE6960119F);
+impl_algorithm!(Mceliece8192128, mceliece8192128, MCELIECE8192128);
+impl_algorithm!(Mceliece8192128f, mceliece8192128f, MCELIECE8192128F);
+
+impl_algorithm!(Newhope512cca, newhope512cca, NEWHOPE512CCA);
+impl_algorithm!(Newhope512cpa, newhope512cpa, NEWHOPE512CPA);
+impl_algorithm!(Newhope1024cca, newhope1024cca, NEWHOPE1024CCA);
+impl_algorithm!(Newhope1024cpa, newhope1024cpa, NEWHOPE1024CPA);
+
+impl_algorithm!(Ntruhps2048509, ntruhps2048509, NTRUHPS2048509);
+impl_algorithm!(Ntruhps2048677, ntruhps2048677, NTRUHPS2048677);
+impl_algorithm!(Ntruhps4096821, ntruhps4096821, NTRUHPS4096821);
+
+impl_algorithm!(Ntruhrss701, ntruhrss701, NTRUHRSS701);
+
+impl_algorithm!(Papabear, papabear, PAPABEAR);
+impl_algorithm!(Papabearephem, papabearephem, PAPABEAREPHEM);
+impl_algorithm!(Saber, saber, SABER);*/
+
+/// Like [crate::PostQuantumContainer], but with the KEM and AEAD fixed at compile time, so that nothing is boxed
+/// or dispatched dynamically. Convert it into a [crate::PostQuantumContainer] to serialize it
+pub struct Container<K: Algorithm, A: Aead = AeadKey> {
+    public_key: K::PublicKey,
+    secret_key: Option<K::SecretKey>,
+    ciphertext: Option<K::Ciphertext>,
+    shared_secret: Option<K::SharedSecret>,
+    aead: Option<A>,
+    anti_replay_attack: AntiReplayAttackContainerOrdered,
+    node: PQNode,
+    negotiation: Option<[u8; 32]>
+}
+
+impl<K: Algorithm, A: Aead> Container<K, A> {
+    /// Creates a new [Container] for Alice
+    pub fn new_alice() -> Self {
+        let (public_key, secret_key) = K::keypair();
+        Self::alice_from_keypair(public_key, secret_key)
+    }
+
+    /// Creates a new [Container] for Alice, drawing all randomness from `rng` rather than the operating system
+    #[cfg(feature = "std")]
+    pub fn new_alice_with_rng<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut rng = rng;
+        let (public_key, secret_key) = K::keypair_with_rng(&mut rng);
+        Self::alice_from_keypair(public_key, secret_key)
+    }
+
+    /// Creates a new [Container] for Alice whose keypair is derived deterministically from `seed`. The seed must be
+    /// kept as secret as the key
+    #[cfg(feature = "std")]
+    pub fn new_alice_from_seed(seed: &[u8; SEED_LENGTH_BYTES]) -> Self {
+        let (public_key, secret_key) = K::keypair_from_seed(seed);
+        Self::alice_from_keypair(public_key, secret_key)
+    }
+
+    fn alice_from_keypair(public_key: K::PublicKey, secret_key: K::SecretKey) -> Self {
+        Self {
+            public_key,
+            secret_key: Some(secret_key),
+            ciphertext: None,
+            shared_secret: None,
+            aead: None,
+            anti_replay_attack: AntiReplayAttackContainerOrdered::default(),
+            node: PQNode::Alice,
+            negotiation: None
+        }
+    }
+
+    /// Creates a new [Container] for Bob. Returns an error if the public key is malformed
+    pub fn new_bob(public_key: &[u8]) -> Result<Self, EzError> {
+        let public_key = K::PublicKey::from_bytes(public_key)?;
+        let (shared_secret, ciphertext) = K::encapsulate(&public_key);
+        let mut container = Self {
+            public_key,
+            secret_key: None,
+            ciphertext: Some(ciphertext),
+            shared_secret: Some(shared_secret),
+            aead: None,
+            anti_replay_attack: AntiReplayAttackContainerOrdered::default(),
+            node: PQNode::Bob,
+            negotiation: None
+        };
+
+        container.load_aead_key()?;
+        Ok(container)
+    }
+
+    /// Restores the state saved in `export`. The algorithm and AEAD suite must already have been checked
+    pub(crate) fn from_export(export: &PostQuantumExport) -> Result<Self, EzError> {
+        let mut container = Self {
+            public_key: K::PublicKey::from_bytes(&export.public_key)?,
+            secret_key: export.secret_key.as_deref().map(K::SecretKey::from_bytes).transpose()?,
+            ciphertext: export.ciphertext.as_deref().map(K::Ciphertext::from_bytes).transpose()?,
+            shared_secret: export.shared_secret.as_deref().map(K::SharedSecret::from_bytes).transpose()?,
+            aead: None,
+            anti_replay_attack: AntiReplayAttackContainerOrdered::from_counters(export.in_counter, export.out_counter),
+            node: export.node,
+            negotiation: export.negotiation.as_deref().map(|transcript| {
+                <[u8; 32]>::try_from(transcript).map_err(|_| EzError::InvalidLength { what: "negotiation transcript", expected: 32, actual: transcript.len() })
+            }).transpose()?
+        };
+
+        if container.shared_secret.is_some() {
+            container.load_aead_key()?;
+        }
+
+        Ok(container)
+    }
+
+    fn load_aead_key(&mut self) -> Result<(), EzError> {
+        let shared_secret = self.get_shared_secret()?;
+        if shared_secret.len() != 32 {
+            return Err(EzError::InvalidLength { what: "shared secret", expected: 32, actual: shared_secret.len() });
+        }
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        match &self.negotiation {
+            Some(transcript) => Hkdf::<Sha256>::new(Some(b"ez_pqcrypto negotiated aead"), shared_secret).expand(transcript, &mut *key).map_err(|_| EzError::LimitExceeded("requested too much keying material"))?,
+            None => key.copy_from_slice(shared_secret)
+        }
+
+        self.aead = Some(A::new(GenericArray::from_slice(&*key)));
+        Ok(())
+    }
+
+    /// Binds the negotiation transcript into the AEAD key and the exporters, re-deriving the AEAD key if it is already loaded
+    pub(crate) fn bind_negotiation(&mut self, transcript: [u8; 32]) -> Result<(), EzError> {
+        self.negotiation = Some(transcript);
+        if self.shared_secret.is_some() {
+            self.load_aead_key()?;
+        }
+
+        Ok(())
+    }
+
+    /// Internally creates shared key after bob sends a response back to Alice
+    pub fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), EzError> {
+        if self.node == PQNode::Bob {
+            return Err(EzError::WrongRole { role: self.node, operation: "alice_on_receive_ciphertext" });
+        }
+
+        // These functions should only be called once upon response back from Bob
+        if self.shared_secret.is_some() || self.ciphertext.is_some() {
+            return Err(EzError::InvalidInput("ciphertext already received"));
+        }
+
+        let ciphertext = K::Ciphertext::from_bytes(ciphertext)?;
+        let secret_key = self.secret_key.as_ref().ok_or(EzError::HandshakeIncomplete { missing: "secret key" })?;
+        self.shared_secret = Some(K::decapsulate(&ciphertext, secret_key));
+        self.ciphertext = Some(ciphertext);
+        self.load_aead_key()
+    }
+
+    /// Gets the public key
+    pub fn get_public_key(&self) -> &[u8] {
+        self.public_key.as_bytes()
+    }
+
+    /// Gets the secret key (If node is Alice type). Exposed through [crate::hazmat::SecretAccess]
+    pub(crate) fn get_secret_key(&self) -> Result<&[u8], EzError> {
+        if self.node == PQNode::Bob {
+            return Err(EzError::WrongRole { role: self.node, operation: "get_secret_key" });
+        }
+
+        self.secret_key.as_ref().map(|secret_key| secret_key.as_bytes()).ok_or(EzError::HandshakeIncomplete { missing: "secret key" })
+    }
+
+    /// Gets the ciphertext
+    pub fn get_ciphertext(&self) -> Result<&[u8], EzError> {
+        self.ciphertext.as_ref().map(|ciphertext| ciphertext.as_bytes()).ok_or(EzError::HandshakeIncomplete { missing: "ciphertext" })
+    }
+
+    /// Gets the shared secret. Exposed through [crate::hazmat::SecretAccess]
+    pub(crate) fn get_shared_secret(&self) -> Result<&[u8], EzError> {
+        self.shared_secret.as_ref().map(|shared_secret| shared_secret.as_bytes()).ok_or(EzError::HandshakeIncomplete { missing: "shared secret" })
+    }
+
+    /// Returns either Alice or Bob
+    pub fn get_node_type(&self) -> PQNode {
+        self.node
+    }
+
+    /// Returns the SHA-256 hash of the public key, suitable for identifying or pinning a peer's key
+    pub fn public_key_fingerprint(&self) -> [u8; 32] {
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(&Sha256::digest(self.get_public_key()));
+        fingerprint
+    }
+
+    /// Derives a value from the shared secret and the exchanged public key and ciphertext. Both sides
+    /// compute the same value, so exchanging it confirms that both hold the same shared secret without revealing it
+    pub fn key_confirmation(&self) -> Result<[u8; 32], EzError> {
+        self.key_confirmation_for(K::ALGORITHM)
+    }
+
+    /// [Container::key_confirmation], binding `algorithm` as the algorithm byte
+    pub(crate) fn key_confirmation_for(&self, algorithm: u8) -> Result<[u8; 32], EzError> {
+        let ciphertext = self.get_ciphertext().map_err(|_| EzError::HandshakeIncomplete { missing: "shared secret" })?;
+        let mut transcript = Vec::with_capacity(1 + self.get_public_key().len() + ciphertext.len());
+        transcript.push(algorithm);
+        transcript.extend_from_slice(self.get_public_key());
+        transcript.extend_from_slice(ciphertext);
+
+        let okm = self.export_keying_material(b"ez_pqcrypto key confirmation", &transcript, 32)?;
+        let mut confirmation = [0u8; 32];
+        confirmation.copy_from_slice(&okm);
+        Ok(confirmation)
+    }
+
+    /// Verifies, in constant time, a key confirmation value received from the other side
+    pub fn verify_key_confirmation(&self, other: &[u8]) -> bool {
+        self.key_confirmation().map(|confirmation| bool::from(confirmation.ct_eq(other))).unwrap_or(false)
+    }
+
+    /// Derives `len` bytes of keying material from the shared secret using HKDF-SHA256. Different `label`s and
+    /// `context`s yield independent keys, none of which reveal the shared secret or the AEAD key
+    pub fn export_keying_material(&self, label: &[u8], context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        let shared_secret = self.get_shared_secret().map_err(|_| EzError::HandshakeIncomplete { missing: "shared secret" })?;
+        if len > MAX_EXPORT_LENGTH {
+            return Err(EzError::LimitExceeded("requested too much keying material"));
+        }
+
+        let salt = self.negotiation.as_ref().map_or(&b"ez_pqcrypto exporter"[..], |transcript| &transcript[..]);
+        let hkdf = Hkdf::<Sha256>::new(Some(salt), shared_secret);
+        let mut okm = Zeroizing::new(vec![0u8; len]);
+        hkdf.expand_multi_info(&[&(label.len() as u64).to_be_bytes(), label, context], &mut okm).map_err(|_| EzError::LimitExceeded("requested too much keying material"))?;
+        Ok(okm)
+    }
+
+    /// Compares the shared secret against `other` in constant time. Returns false if the shared secret is not loaded
+    pub fn shared_secret_eq(&self, other: &[u8]) -> bool {
+        self.get_shared_secret().map(|shared_secret| bool::from(shared_secret.ct_eq(other))).unwrap_or(false)
+    }
+
+    fn get_aead(&self) -> Result<&A, EzError> {
+        self.aead.as_ref().ok_or(EzError::HandshakeIncomplete { missing: "shared secret" })
+    }
+
+    /// Encrypts the data. This will return an error if the internal shared secret is not set
+    pub fn encrypt<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<Vec<u8>, EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        self.get_aead()?.encrypt(nonce, input.as_ref()).map_err(|_| EzError::EncryptionFailure)
+    }
+
+    /// Decrypts the data. This will return an error if the internal shared secret is not set
+    pub fn decrypt<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<Vec<u8>, EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        self.get_aead()?.decrypt(nonce, input.as_ref()).map_err(|_| EzError::AuthenticationFailed)
+    }
+
+    /// Encrypts the payload after the first `header_len` bytes in place, appending the PID and tag, and authenticates the header
+    pub fn protect_packet_in_place<R: AsRef<[u8]>>(&self, header_len: usize, full_packet: &mut BytesMut, nonce: R) -> Result<(), EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        if header_len > full_packet.len() {
+            return Err(EzError::InvalidLength { what: "packet", expected: header_len, actual: full_packet.len() });
+        }
+
+        let aead = self.get_aead()?;
+        let mut payload = full_packet.split_off(header_len);
+        let header = full_packet;
+
+        // next, push the ARA-generated PID
+        payload.put_u64(self.anti_replay_attack.get_next_pid());
+        let payload_len = payload.len();
+
+        let mut in_place_payload = InPlaceBytesMut::new(&mut payload, 0..payload_len).ok_or(EzError::InvalidInput("bad window range"))?;
+        let result = aead.encrypt_in_place(nonce, &header[0..header_len], &mut in_place_payload).map_err(|_| EzError::EncryptionFailure);
+        header.unsplit(payload);
+        result
+    }
+
+    /// Validates the AAD (header) and produces the plaintext given the input of ciphertext
+    pub fn validate_packet_in_place<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut BytesMut, nonce: R) -> Result<(), EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        let aead = self.get_aead()?;
+        let payload_len = payload.len();
+
+        let mut in_place_payload = InPlaceBytesMut::new(payload, 0..payload_len).ok_or(EzError::InvalidInput("bad window range"))?;
+        aead.decrypt_in_place(nonce, header.as_ref(), &mut in_place_payload).map_err(|_| EzError::AuthenticationFailed)?;
+
+        // get the last 8 bytes of the payload
+        let end_idx = payload.len();
+        let start_idx = end_idx.saturating_sub(8);
+        if end_idx - start_idx == 8 {
+            let mut array: [u8; 8] = Default::default();
+            array.copy_from_slice(&payload[start_idx..end_idx]);
+
+            if self.anti_replay_attack.on_pid_received(u64::from_be_bytes(array)) {
+                // remove the PID from the payload
+                payload.truncate(start_idx);
+                return Ok(())
+            }
+        }
+
+        Err(EzError::ReplayDetected)
+    }
+
+    /// An allocation-free version of [Container::protect_packet_in_place]. The first `packet_len` bytes of `buffer`
+    /// hold the header and payload, and the buffer must have at least [PACKET_OVERHEAD_BYTES] of tail room for the PID and tag.
+    /// Returns the length of the protected packet, or an error if the tail room is too short
+    pub fn protect_packet_in_slice<R: AsRef<[u8]>>(&self, header_len: usize, buffer: &mut [u8], packet_len: usize, nonce: R) -> Result<usize, EzError> {
+        if header_len > packet_len || packet_len > buffer.len() {
+            return Err(EzError::InvalidInput("bad window range"));
+        }
+
+        if buffer.len() - packet_len < PACKET_OVERHEAD_BYTES {
+            return Err(EzError::InsufficientCapacity { required: packet_len + PACKET_OVERHEAD_BYTES, available: buffer.len() });
+        }
+
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        let aead = self.get_aead()?;
+        let (header, payload) = buffer.split_at_mut(header_len);
+        let mut in_place_payload = InPlaceSlice::new(payload, packet_len - header_len).ok_or(EzError::InvalidInput("bad window range"))?;
+
+        in_place_payload.extend_from_slice(&self.anti_replay_attack.get_next_pid().to_be_bytes()).map_err(|_| EzError::InsufficientCapacity { required: packet_len + PACKET_OVERHEAD_BYTES, available: packet_len })?;
+        aead.encrypt_in_place(nonce, header, &mut in_place_payload).map_err(|_| EzError::EncryptionFailure)?;
+        Ok(header_len + in_place_payload.len())
+    }
+
+    /// An allocation-free version of [Container::validate_packet_in_place]. The plaintext is left at the start of
+    /// `payload`, and its length is returned
+    pub fn validate_packet_in_slice<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut [u8], nonce: R) -> Result<usize, EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        let aead = self.get_aead()?;
+        let payload_len = payload.len();
+        let mut in_place_payload = InPlaceSlice::new(payload, payload_len).ok_or(EzError::InvalidInput("bad window range"))?;
+
+        aead.decrypt_in_place(nonce, header.as_ref(), &mut in_place_payload).map_err(|_| EzError::AuthenticationFailed)?;
+
+        let end_idx = in_place_payload.len();
+        let start_idx = end_idx.checked_sub(8).ok_or(EzError::InvalidLength { what: "payload", expected: 8, actual: end_idx })?;
+        let mut array: [u8; 8] = Default::default();
+        array.copy_from_slice(&in_place_payload.as_ref()[start_idx..end_idx]);
+
+        if self.anti_replay_attack.on_pid_received(u64::from_be_bytes(array)) {
+            Ok(start_idx)
+        } else {
+            Err(EzError::ReplayDetected)
+        }
+    }
+
+    /// Like [Container::encrypt], but returns the authentication tag separately from the ciphertext
+    pub fn encrypt_detached<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<(Vec<u8>, Tag), EzError> {
+        let mut buffer = input.as_ref().to_vec();
+        let tag = self.encrypt_in_place_detached(&mut buffer, &[], nonce)?;
+        Ok((buffer, tag))
+    }
+
+    /// Decrypts a ciphertext produced by [Container::encrypt_detached]
+    pub fn decrypt_detached<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, tag: &Tag, nonce: R) -> Result<Vec<u8>, EzError> {
+        let mut buffer = input.as_ref().to_vec();
+        self.decrypt_in_place_detached(&mut buffer, &[], tag, nonce)?;
+        Ok(buffer)
+    }
+
+    /// Encrypts `buffer` in place, authenticating `aad` alongside it, and returns the tag
+    pub fn encrypt_in_place_detached<R: AsRef<[u8]>>(&self, buffer: &mut [u8], aad: &[u8], nonce: R) -> Result<Tag, EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        self.get_aead()?.encrypt_in_place_detached(nonce, aad, buffer).map_err(|_| EzError::EncryptionFailure)
+    }
+
+    /// Decrypts `buffer` in place if `tag` authenticates it and `aad`
+    pub fn decrypt_in_place_detached<R: AsRef<[u8]>>(&self, buffer: &mut [u8], aad: &[u8], tag: &Tag, nonce: R) -> Result<(), EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        self.get_aead()?.decrypt_in_place_detached(nonce, aad, buffer, tag).map_err(|_| EzError::AuthenticationFailed)
+    }
+
+    /// Like [Container::protect_packet_in_place], but the tag is returned instead of appended. The PID is still
+    /// appended to the payload
+    pub fn protect_packet_in_place_detached<R: AsRef<[u8]>>(&self, header_len: usize, full_packet: &mut BytesMut, nonce: R) -> Result<Tag, EzError> {
+        if header_len > full_packet.len() {
+            return Err(EzError::InvalidLength { what: "packet", expected: header_len, actual: full_packet.len() });
+        }
+
+        full_packet.put_u64(self.anti_replay_attack.get_next_pid());
+        let (header, payload) = full_packet.split_at_mut(header_len);
+        self.encrypt_in_place_detached(payload, header, nonce)
+    }
+
+    /// Validates a packet protected by [Container::protect_packet_in_place_detached]. On success, the PID is
+    /// removed from the payload, leaving the plaintext
+    pub fn validate_packet_in_place_detached<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut BytesMut, tag: &Tag, nonce: R) -> Result<(), EzError> {
+        self.decrypt_in_place_detached(payload, header.as_ref(), tag, nonce)?;
+
+        let start_idx = payload.len().checked_sub(8).ok_or(EzError::InvalidLength { what: "payload", expected: 8, actual: payload.len() })?;
+        let mut array: [u8; 8] = Default::default();
+        array.copy_from_slice(&payload[start_idx..]);
+
+        if self.anti_replay_attack.on_pid_received(u64::from_be_bytes(array)) {
+            payload.truncate(start_idx);
+            Ok(())
+        } else {
+            Err(EzError::ReplayDetected)
+        }
+    }
+
+    /// Like [Container::protect_packet_in_place_detached], but the header may be scattered across several buffers and
+    /// the payload is encrypted where it lies, without being moved or grown. Returns the tag and the PID, both of which
+    /// must be sent with the packet. The PID is authenticated along with the header
+    ///
+    /// The payload must be a single buffer: the AEADs cannot encrypt a message split across buffers without first
+    /// copying it into one
+    #[cfg(feature = "std")]
+    pub fn protect_packet_vectored<R: AsRef<[u8]>>(&self, header: &[IoSlice], payload: &mut [u8], nonce: R) -> Result<(Tag, u64), EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        let aead = self.get_aead()?;
+        let pid = self.anti_replay_attack.get_next_pid();
+        let tag = aead.encrypt_in_place_detached(nonce, &vectored_aad(header, pid), payload).map_err(|_| EzError::EncryptionFailure)?;
+        Ok((tag, pid))
+    }
+
+    /// Validates a packet protected by [Container::protect_packet_vectored], using the `tag` and `pid` sent with it, and
+    /// decrypts the payload in place. On failure, the payload is zeroed
+    #[cfg(feature = "std")]
+    pub fn validate_packet_vectored<R: AsRef<[u8]>>(&self, header: &[IoSlice], payload: &mut [u8], tag: &Tag, pid: u64, nonce: R) -> Result<(), EzError> {
+        let nonce = nonce_from_slice(nonce.as_ref())?;
+        let aead = self.get_aead()?;
+
+        if aead.decrypt_in_place_detached(nonce, &vectored_aad(header, pid), payload, tag).is_err() {
+            payload.iter_mut().for_each(|byte| *byte = 0);
+            return Err(EzError::AuthenticationFailed)
+        }
+
+        if self.anti_replay_attack.on_pid_received(pid) {
+            Ok(())
+        } else {
+            Err(EzError::ReplayDetected)
+        }
+    }
+}
+
+/// The header segments followed by the PID. Only the header, never the payload, is copied
+#[cfg(feature = "std")]
+fn vectored_aad(header: &[IoSlice], pid: u64) -> Vec<u8> {
+    let header_len = header.iter().map(|slice| slice.len()).sum::<usize>();
+    let mut aad = Vec::with_capacity(header_len + 8);
+    header.iter().for_each(|slice| aad.extend_from_slice(slice));
+    aad.extend_from_slice(&pid.to_be_bytes());
+    aad
+}
+
+/// Returns the shared secret encapsulated in `ciphertext`, without needing Alice's public key
+pub(crate) fn decapsulate<K: Algorithm>(secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, EzError> {
+    let secret_key = K::SecretKey::from_bytes(secret_key)?;
+    let ciphertext = K::Ciphertext::from_bytes(ciphertext)?;
+    let shared_secret = K::decapsulate(&ciphertext, &secret_key);
+
+    Ok(Zeroizing::new(shared_secret.as_bytes().to_vec()))
+}
+
+impl<K: Algorithm, A: Aead> Drop for Container<K, A> {
+    fn drop(&mut self) {
+        if let Some(aead) = self.aead.as_mut() {
+            aead.wipe();
+        }
+    }
+}
+
+impl<K: Algorithm, A: Aead> core::fmt::Debug for Container<K, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Container")
+            .field("algorithm", &K::ALGORITHM)
+            .field("node", &self.node)
+            .field("negotiated", &self.negotiation.is_some())
+            .field("public_key_fingerprint", &hex_string(&self.public_key_fingerprint()))
+            .field("secret_key", &self.get_secret_key().ok().map(|_| "<redacted>"))
+            .field("shared_secret", &self.get_shared_secret().ok().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl<K: Algorithm, A: Aead> crate::hazmat::SecretAccess for Container<K, A> {
+    fn get_secret_key(&self) -> Result<&[u8], EzError> {
+        Container::get_secret_key(self)
+    }
+
+    fn get_shared_secret(&self) -> Result<&[u8], EzError> {
+        Container::get_shared_secret(self)
+    }
+}
+
+impl<K: Algorithm> PostQuantumType for Container<K> {
+    fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), EzError> {
+        Container::alice_on_receive_ciphertext(self, ciphertext)
+    }
+
+    fn get_public_key(&self) -> &[u8] {
+        Container::get_public_key(self)
+    }
+
+    fn get_secret_key(&self) -> Result<&[u8], EzError> {
+        Container::get_secret_key(self)
+    }
+
+    fn get_ciphertext(&self) -> Result<&[u8], EzError> {
+        Container::get_ciphertext(self)
+    }
+
+    fn get_shared_secret(&self) -> Result<&[u8], EzError> {
+        Container::get_shared_secret(self)
+    }
+
+    fn get_node_type(&self) -> PQNode {
+        self.node
+    }
+
+    fn get_counters(&self) -> (u64, u64) {
+        self.anti_replay_attack.get_counters()
+    }
+
+    #[cfg(feature = "std")]
+    fn get_aead(&self) -> Option<&AeadKey> {
+        self.aead.as_ref()
+    }
+
+    fn key_confirmation(&self, algorithm: u8) -> Result<[u8; 32], EzError> {
+        self.key_confirmation_for(algorithm)
+    }
+
+    fn bind_negotiation(&mut self, transcript: [u8; 32]) -> Result<(), EzError> {
+        Container::bind_negotiation(self, transcript)
+    }
+
+    fn get_negotiation(&self) -> Option<&[u8; 32]> {
+        self.negotiation.as_ref()
+    }
+
+    fn export_keying_material(&self, label: &[u8], context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        Container::export_keying_material(self, label, context, len)
+    }
+
+    fn encrypt(&self, input: &[u8], nonce: &[u8]) -> Result<Vec<u8>, EzError> {
+        Container::encrypt(self, input, nonce)
+    }
+
+    fn decrypt(&self, input: &[u8], nonce: &[u8]) -> Result<Vec<u8>, EzError> {
+        Container::decrypt(self, input, nonce)
+    }
+
+    fn protect_packet_in_place(&self, header_len: usize, full_packet: &mut BytesMut, nonce: &[u8]) -> Result<(), EzError> {
+        Container::protect_packet_in_place(self, header_len, full_packet, nonce)
+    }
+
+    fn validate_packet_in_place(&self, header: &[u8], payload: &mut BytesMut, nonce: &[u8]) -> Result<(), EzError> {
+        Container::validate_packet_in_place(self, header, payload, nonce)
+    }
+
+    fn protect_packet_in_slice(&self, header_len: usize, buffer: &mut [u8], packet_len: usize, nonce: &[u8]) -> Result<usize, EzError> {
+        Container::protect_packet_in_slice(self, header_len, buffer, packet_len, nonce)
+    }
+
+    fn validate_packet_in_slice(&self, header: &[u8], payload: &mut [u8], nonce: &[u8]) -> Result<usize, EzError> {
+        Container::validate_packet_in_slice(self, header, payload, nonce)
+    }
+
+    fn encrypt_in_place_detached(&self, buffer: &mut [u8], aad: &[u8], nonce: &[u8]) -> Result<Tag, EzError> {
+        Container::encrypt_in_place_detached(self, buffer, aad, nonce)
+    }
+
+    fn decrypt_in_place_detached(&self, buffer: &mut [u8], aad: &[u8], tag: &Tag, nonce: &[u8]) -> Result<(), EzError> {
+        Container::decrypt_in_place_detached(self, buffer, aad, tag, nonce)
+    }
+
+    fn protect_packet_in_place_detached(&self, header_len: usize, full_packet: &mut BytesMut, nonce: &[u8]) -> Result<Tag, EzError> {
+        Container::protect_packet_in_place_detached(self, header_len, full_packet, nonce)
+    }
+
+    fn validate_packet_in_place_detached(&self, header: &[u8], payload: &mut BytesMut, tag: &Tag, nonce: &[u8]) -> Result<(), EzError> {
+        Container::validate_packet_in_place_detached(self, header, payload, tag, nonce)
+    }
+
+    #[cfg(feature = "std")]
+    fn protect_packet_vectored(&self, header: &[IoSlice], payload: &mut [u8], nonce: &[u8]) -> Result<(Tag, u64), EzError> {
+        Container::protect_packet_vectored(self, header, payload, nonce)
+    }
+
+    #[cfg(feature = "std")]
+    fn validate_packet_vectored(&self, header: &[IoSlice], payload: &mut [u8], tag: &Tag, pid: u64, nonce: &[u8]) -> Result<(), EzError> {
+        Container::validate_packet_vectored(self, header, payload, tag, pid, nonce)
+    }
+}
@@ -0,0 +1,358 @@
+use nanoserde::{DeBin, DeBinErr, SerBin};
+use rand::RngCore;
+#[cfg(feature = "chacha20")]
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+#[cfg(not(feature = "chacha20"))]
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+use crate::{PostQuantumContainer, NONCE_LENGTH_BYTES};
+use crate::ez_error::EzError;
+use crate::export::{de_bounded_len, de_bytes};
+
+/// The magic bytes at the start of every serialized [PostQuantumEnvelope]
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"EZSE";
+/// The magic bytes at the start of every serialized [MultiRecipientEnvelope]
+pub const MULTI_ENVELOPE_MAGIC: [u8; 4] = *b"EZME";
+/// The current version of the serialized envelope formats
+pub const ENVELOPE_FORMAT_VERSION: u8 = 1;
+/// The length of the key IDs that tag each recipient entry of a [MultiRecipientEnvelope]
+pub const KEY_ID_LENGTH: usize = 8;
+/// The length of the random content key that encrypts the body of a [MultiRecipientEnvelope]
+const CONTENT_KEY_LENGTH: usize = 32;
+/// The smallest possible serialized [RecipientEntry]: the algorithm byte and four empty length-prefixed fields
+const MIN_RECIPIENT_ENTRY_LENGTH: usize = 1 + (4 * 8);
+/// Separates the key of a [PostQuantumEnvelope] body from every other key derived from a KEM shared secret
+const ENVELOPE_LABEL: &[u8] = b"ez_pqcrypto envelope";
+/// Separates the key that wraps a [RecipientEntry]'s content key
+const RECIPIENT_WRAP_LABEL: &[u8] = b"ez_pqcrypto recipient wrap";
+
+/// Prefixes `body` with `magic` and [ENVELOPE_FORMAT_VERSION]
+fn with_header(magic: &[u8; 4], body: Vec<u8>) -> Vec<u8> {
+    let mut output = Vec::with_capacity(magic.len() + 1 + body.len());
+    output.extend_from_slice(magic);
+    output.push(ENVELOPE_FORMAT_VERSION);
+    output.extend_from_slice(&body);
+    output
+}
+
+/// Checks the magic and version written by [with_header], returning the bytes that follow them
+fn strip_header<'a>(magic: &[u8; 4], what: &'static str, bytes: &'a [u8]) -> Result<&'a [u8], EzError> {
+    if bytes.len() <= magic.len() {
+        return Err(EzError::InvalidLength { what, expected: magic.len() + 1, actual: bytes.len() });
+    }
+
+    if !bytes.starts_with(magic) {
+        return Err(EzError::InvalidInput("missing envelope magic bytes"));
+    }
+
+    match bytes[magic.len()] {
+        ENVELOPE_FORMAT_VERSION => Ok(&bytes[magic.len() + 1..]),
+        version => Err(EzError::UnsupportedVersion(version))
+    }
+}
+
+/// Derives a single-use AEAD key from a KEM shared secret with HKDF-SHA256, bound to `label`, the algorithm byte
+/// and the KEM ciphertext
+fn derive_key(label: &[u8], algorithm: u8, kem_ciphertext: &[u8], shared_secret: &[u8]) -> Result<AeadKey, EzError> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(label), shared_secret).expand_multi_info(&[&[algorithm], kem_ciphertext], &mut *key).map_err(|_| EzError::LimitExceeded("requested too much keying material"))?;
+    Ok(AeadKey::new(GenericArray::from_slice(&*key)))
+}
+
+/// A payload sealed to a recipient's long-term public key. It carries everything the recipient
+/// needs besides their secret key: the algorithm byte, the KEM ciphertext, and the AEAD-encrypted body
+#[derive(SerBin)]
+pub struct PostQuantumEnvelope {
+    pub(crate) algorithm: u8,
+    pub(crate) kem_ciphertext: Vec<u8>,
+    pub(crate) nonce: Vec<u8>,
+    pub(crate) body: Vec<u8>
+}
+
+impl DeBin for PostQuantumEnvelope {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(Self {
+            algorithm: DeBin::de_bin(o, d)?,
+            kem_ciphertext: de_bytes(o, d)?,
+            nonce: de_bytes(o, d)?,
+            body: de_bytes(o, d)?
+        })
+    }
+}
+
+impl PostQuantumEnvelope {
+    /// Returns the byte-sized representation of the algorithm used
+    pub fn get_algorithm_idx(&self) -> u8 {
+        self.algorithm
+    }
+
+    /// Serializes the envelope to a vector, starting with [ENVELOPE_MAGIC] and [ENVELOPE_FORMAT_VERSION]
+    pub fn serialize_to_vector(&self) -> Vec<u8> {
+        with_header(&ENVELOPE_MAGIC, self.serialize_bin())
+    }
+
+    /// Attempts to deserialize the input bytes into a [PostQuantumEnvelope]. Returns [EzError::UnsupportedVersion]
+    /// for envelopes written by a newer format version
+    pub fn deserialize_from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, EzError> {
+        PostQuantumEnvelope::deserialize_bin(strip_header(&ENVELOPE_MAGIC, "envelope", bytes.as_ref())?).map_err(EzError::deserialize)
+    }
+
+    /// The algorithm byte and KEM ciphertext are authenticated alongside the body
+    fn associated_data(algorithm: u8, kem_ciphertext: &[u8]) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(1 + kem_ciphertext.len());
+        aad.push(algorithm);
+        aad.extend_from_slice(kem_ciphertext);
+        aad
+    }
+}
+
+/// Encrypts `plaintext` to the holder of the secret key belonging to `recipient_public_key`.
+/// Internally, this acts as Bob: a fresh shared secret is encapsulated against the public key,
+/// and the key derived from it is used once to encrypt the body
+pub fn seal_to<T: AsRef<[u8]>>(recipient_public_key: &[u8], algorithm: u8, plaintext: T) -> Result<PostQuantumEnvelope, EzError> {
+    crate::check_backend(algorithm)?;
+
+    let bob_container = PostQuantumContainer::new_bob(algorithm, recipient_public_key)?;
+    let kem_ciphertext = bob_container.get_ciphertext()?.to_vec();
+    let mut aes_gcm_key = derive_key(ENVELOPE_LABEL, algorithm, &kem_ciphertext, bob_container.get_shared_secret()?)?;
+
+    let mut nonce = vec![0u8; NONCE_LENGTH_BYTES];
+    crate::default_rng().fill_bytes(&mut nonce);
+
+    let aad = PostQuantumEnvelope::associated_data(algorithm, &kem_ciphertext);
+    let body = aes_gcm_key.encrypt(GenericArray::from_slice(&nonce), Payload { msg: plaintext.as_ref(), aad: &aad });
+    crate::wipe_aead_key(&mut aes_gcm_key);
+    let body = body.map_err(|_| EzError::EncryptionFailure)?;
+
+    Ok(PostQuantumEnvelope { algorithm, kem_ciphertext, nonce, body })
+}
+
+/// Decrypts an envelope created by [seal_to] using the recipient's secret key
+pub fn open(envelope: &PostQuantumEnvelope, secret_key: &[u8]) -> Result<Vec<u8>, EzError> {
+    crate::check_backend(envelope.algorithm)?;
+
+    if envelope.nonce.len() != NONCE_LENGTH_BYTES {
+        return Err(EzError::InvalidLength { what: "nonce", expected: NONCE_LENGTH_BYTES, actual: envelope.nonce.len() });
+    }
+
+    let shared_secret = PostQuantumContainer::get_decapsulation(envelope.algorithm, secret_key, &envelope.kem_ciphertext)?;
+    let mut aes_gcm_key = derive_key(ENVELOPE_LABEL, envelope.algorithm, &envelope.kem_ciphertext, &shared_secret)?;
+
+    let aad = PostQuantumEnvelope::associated_data(envelope.algorithm, &envelope.kem_ciphertext);
+    let plaintext = aes_gcm_key.decrypt(GenericArray::from_slice(&envelope.nonce), Payload { msg: &envelope.body, aad: &aad });
+    crate::wipe_aead_key(&mut aes_gcm_key);
+    plaintext.map_err(|_| EzError::AuthenticationFailed)
+}
+
+/// Computes the key ID of a public key: the truncated SHA-256 hash of the public key
+pub fn key_id(public_key: &[u8]) -> [u8; KEY_ID_LENGTH] {
+    let mut id = [0u8; KEY_ID_LENGTH];
+    id.copy_from_slice(&Sha256::digest(public_key)[..KEY_ID_LENGTH]);
+    id
+}
+
+/// A single recipient's wrapping of the content key
+#[derive(SerBin)]
+pub struct RecipientEntry {
+    pub(crate) algorithm: u8,
+    pub(crate) key_id: Vec<u8>,
+    pub(crate) kem_ciphertext: Vec<u8>,
+    pub(crate) nonce: Vec<u8>,
+    pub(crate) wrapped_key: Vec<u8>
+}
+
+impl DeBin for RecipientEntry {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(Self {
+            algorithm: DeBin::de_bin(o, d)?,
+            key_id: de_bytes(o, d)?,
+            kem_ciphertext: de_bytes(o, d)?,
+            nonce: de_bytes(o, d)?,
+            wrapped_key: de_bytes(o, d)?
+        })
+    }
+}
+
+impl RecipientEntry {
+    /// Returns the byte-sized representation of the algorithm used for this recipient
+    pub fn get_algorithm_idx(&self) -> u8 {
+        self.algorithm
+    }
+
+    /// Returns the key ID of the recipient's public key
+    pub fn get_key_id(&self) -> &[u8] {
+        &self.key_id
+    }
+
+    fn associated_data(&self) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(1 + self.key_id.len() + self.kem_ciphertext.len());
+        aad.push(self.algorithm);
+        aad.extend_from_slice(&self.key_id);
+        aad.extend_from_slice(&self.kem_ciphertext);
+        aad
+    }
+
+    /// Attempts to unwrap the content key. A failed decapsulation or a malformed nonce is replaced by a dummy
+    /// so that key derivation and decryption still run, and a failure still returns a (zeroed) key, so that the
+    /// caller can process every entry identically
+    fn unwrap_key(&self, secret_key: &[u8]) -> (Choice, [u8; CONTENT_KEY_LENGTH]) {
+        let mut content_key = [0u8; CONTENT_KEY_LENGTH];
+        let decapsulated = PostQuantumContainer::get_decapsulation(self.algorithm, secret_key, &self.kem_ciphertext);
+        let well_formed = Choice::from((decapsulated.is_ok() && self.nonce.len() == NONCE_LENGTH_BYTES) as u8);
+        let shared_secret = decapsulated.unwrap_or_else(|_| Zeroizing::new(vec![0u8; CONTENT_KEY_LENGTH]));
+        let dummy_nonce = [0u8; NONCE_LENGTH_BYTES];
+        let nonce = if self.nonce.len() == NONCE_LENGTH_BYTES { &self.nonce[..] } else { &dummy_nonce[..] };
+
+        let mut key = derive_key(RECIPIENT_WRAP_LABEL, self.algorithm, &self.kem_ciphertext, &shared_secret).unwrap_or_else(|_| AeadKey::new(&Default::default()));
+        let decrypted = key.decrypt(GenericArray::from_slice(nonce), Payload { msg: &self.wrapped_key, aad: &self.associated_data() }).map(Zeroizing::new);
+        crate::wipe_aead_key(&mut key);
+        let unwrapped = match decrypted {
+            Ok(unwrapped) if unwrapped.len() == CONTENT_KEY_LENGTH => {
+                content_key.copy_from_slice(&unwrapped);
+                well_formed
+            }
+
+            _ => Choice::from(0)
+        };
+
+        for byte in content_key.iter_mut() {
+            byte.conditional_assign(&0, !unwrapped);
+        }
+
+        (unwrapped, content_key)
+    }
+}
+
+/// Hashes the recipient list. The hash authenticates the body, so entries cannot be added, removed, reordered or
+/// altered without the body failing to decrypt
+fn recipients_digest(recipients: &[RecipientEntry]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"ez_pqcrypto recipients");
+    hasher.update((recipients.len() as u64).to_le_bytes());
+    for entry in recipients {
+        hasher.update(entry.serialize_bin());
+    }
+
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&hasher.finalize());
+    digest
+}
+
+/// A payload encrypted once under a random content key, which in turn is wrapped for each
+/// recipient using their own KEM algorithm and public key
+#[derive(SerBin)]
+pub struct MultiRecipientEnvelope {
+    pub(crate) recipients: Vec<RecipientEntry>,
+    pub(crate) nonce: Vec<u8>,
+    pub(crate) body: Vec<u8>
+}
+
+impl DeBin for MultiRecipientEnvelope {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let count = de_bounded_len(o, d, MIN_RECIPIENT_ENTRY_LENGTH)?;
+        let recipients = (0..count).map(|_| RecipientEntry::de_bin(o, d)).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            recipients,
+            nonce: de_bytes(o, d)?,
+            body: de_bytes(o, d)?
+        })
+    }
+}
+
+impl MultiRecipientEnvelope {
+    /// Returns the per-recipient entries
+    pub fn get_recipients(&self) -> &[RecipientEntry] {
+        &self.recipients
+    }
+
+    /// Serializes the envelope to a vector, starting with [MULTI_ENVELOPE_MAGIC] and [ENVELOPE_FORMAT_VERSION]
+    pub fn serialize_to_vector(&self) -> Vec<u8> {
+        with_header(&MULTI_ENVELOPE_MAGIC, self.serialize_bin())
+    }
+
+    /// Attempts to deserialize the input bytes into a [MultiRecipientEnvelope]. Returns [EzError::UnsupportedVersion]
+    /// for envelopes written by a newer format version
+    pub fn deserialize_from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, EzError> {
+        MultiRecipientEnvelope::deserialize_bin(strip_header(&MULTI_ENVELOPE_MAGIC, "multi-recipient envelope", bytes.as_ref())?).map_err(EzError::deserialize)
+    }
+}
+
+/// Encrypts `plaintext` once for all `recipients`, each given as an (algorithm, public key) pair
+pub fn seal_to_many<T: AsRef<[u8]>>(recipients: &[(u8, &[u8])], plaintext: T) -> Result<MultiRecipientEnvelope, EzError> {
+    if recipients.is_empty() {
+        return Err(EzError::InvalidInput("no recipients"));
+    }
+
+    let mut rng = crate::default_rng();
+    let mut content_key = Zeroizing::new([0u8; CONTENT_KEY_LENGTH]);
+    rng.fill_bytes(&mut *content_key);
+
+    let mut entries = Vec::with_capacity(recipients.len());
+    for (algorithm, public_key) in recipients {
+        crate::check_backend(*algorithm)?;
+        let bob_container = PostQuantumContainer::new_bob(*algorithm, public_key)?;
+        let mut entry = RecipientEntry {
+            algorithm: *algorithm,
+            key_id: key_id(public_key).to_vec(),
+            kem_ciphertext: bob_container.get_ciphertext()?.to_vec(),
+            nonce: vec![0u8; NONCE_LENGTH_BYTES],
+            wrapped_key: Vec::new()
+        };
+        let mut aes_gcm_key = derive_key(RECIPIENT_WRAP_LABEL, *algorithm, &entry.kem_ciphertext, bob_container.get_shared_secret()?)?;
+
+        rng.fill_bytes(&mut entry.nonce);
+        let wrapped_key = aes_gcm_key.encrypt(GenericArray::from_slice(&entry.nonce), Payload { msg: &*content_key, aad: &entry.associated_data() });
+        crate::wipe_aead_key(&mut aes_gcm_key);
+        entry.wrapped_key = wrapped_key.map_err(|_| EzError::EncryptionFailure)?;
+        entries.push(entry);
+    }
+
+    let mut nonce = vec![0u8; NONCE_LENGTH_BYTES];
+    rng.fill_bytes(&mut nonce);
+    let aad = recipients_digest(&entries);
+    let mut body_key = AeadKey::new(GenericArray::from_slice(&*content_key));
+    let body = body_key.encrypt(GenericArray::from_slice(&nonce), Payload { msg: plaintext.as_ref(), aad: &aad });
+    crate::wipe_aead_key(&mut body_key);
+    let body = body.map_err(|_| EzError::EncryptionFailure)?;
+
+    Ok(MultiRecipientEnvelope { recipients: entries, nonce, body })
+}
+
+/// Decrypts a [MultiRecipientEnvelope] using the recipient's keypair. Every entry is processed
+/// and the match is selected in constant time, so the timing does not reveal which entry belongs
+/// to the recipient
+pub fn open_multi(envelope: &MultiRecipientEnvelope, public_key: &[u8], secret_key: &[u8]) -> Result<Vec<u8>, EzError> {
+    if envelope.nonce.len() != NONCE_LENGTH_BYTES {
+        return Err(EzError::InvalidLength { what: "nonce", expected: NONCE_LENGTH_BYTES, actual: envelope.nonce.len() });
+    }
+
+    let own_key_id = key_id(public_key);
+    let mut found = Choice::from(0);
+    let mut content_key = Zeroizing::new([0u8; CONTENT_KEY_LENGTH]);
+
+    for entry in &envelope.recipients {
+        let (unwrapped, mut candidate) = entry.unwrap_key(secret_key);
+        let is_ours = entry.key_id.as_slice().ct_eq(&own_key_id[..]) & unwrapped & !found;
+
+        for (byte, candidate_byte) in content_key.iter_mut().zip(candidate.iter()) {
+            byte.conditional_assign(candidate_byte, is_ours);
+        }
+
+        found |= is_ours;
+        candidate.zeroize();
+    }
+
+    if !bool::from(found) {
+        return Err(EzError::AuthenticationFailed);
+    }
+
+    let aad = recipients_digest(&envelope.recipients);
+    let mut body_key = AeadKey::new(GenericArray::from_slice(&*content_key));
+    let plaintext = body_key.decrypt(GenericArray::from_slice(&envelope.nonce), Payload { msg: &envelope.body, aad: &aad });
+    crate::wipe_aead_key(&mut body_key);
+    plaintext.map_err(|_| EzError::AuthenticationFailed)
+}
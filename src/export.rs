@@ -1,51 +1,486 @@
 use crate::{PostQuantumContainer, PQNode};
-use nanoserde::{DeBin, SerBin};
+use crate::ez_error::EzError;
+#[cfg(feature = "std")]
+use nanoserde::{DeBin, DeBinErr};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::convert::TryFrom;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// The magic bytes at the start of every serialized [PostQuantumExport]
+pub const EXPORT_MAGIC: [u8; 4] = *b"EZPQ";
+/// The current version of the serialized format. This is only bumped for changes that older readers cannot skip over
+pub const EXPORT_FORMAT_VERSION: u8 = 1;
+
+/// AES-256-GCM-SIV with a 12-byte nonce
+pub const AEAD_SUITE_AES_256_GCM_SIV: u8 = 0;
+/// XChaCha20-Poly1305 with a 24-byte nonce
+pub const AEAD_SUITE_XCHACHA20_POLY1305: u8 = 1;
+
+/// The AEAD suite compiled into this build
+#[cfg(feature = "chacha20")]
+pub const AEAD_SUITE: u8 = AEAD_SUITE_XCHACHA20_POLY1305;
+/// The AEAD suite compiled into this build
+#[cfg(not(feature = "chacha20"))]
+pub const AEAD_SUITE: u8 = AEAD_SUITE_AES_256_GCM_SIV;
+
+/// Terminates the fields, so that truncation at a field boundary is detected
+const FIELD_END: u8 = 0x00;
+const FIELD_PUBLIC_KEY: u8 = 0x01;
+const FIELD_SECRET_KEY: u8 = 0x02;
+const FIELD_CIPHERTEXT: u8 = 0x03;
+const FIELD_SHARED_SECRET: u8 = 0x04;
+/// Unknown fields with this bit set cannot be safely ignored, so decoding fails. Other unknown fields are skipped
+const FIELD_CRITICAL: u8 = 0x80;
+/// Critical, since a reader that skipped it would derive different keys
+const FIELD_NEGOTIATION: u8 = FIELD_CRITICAL | 0x05;
+
+/// magic, version, algorithm, AEAD suite, role and both replay counters
+const HEADER_LENGTH: usize = 4 + 1 + 1 + 1 + 1 + 8 + 8;
+/// The tag and length prefix of each field
+const FIELD_HEADER_LENGTH: usize = 1 + 4;
 
 /// The default type to store data from a [PostQuantumContainer]
-#[derive(DeBin, SerBin)]
+///
+/// Serialized (version 1, integers big-endian), this is [EXPORT_MAGIC], the format version, the algorithm byte, the AEAD
+/// suite, the role (0 for Alice, 1 for Bob), the inbound and outbound replay counters as u64s, and finally a sequence
+/// of fields, each a one-byte tag, a u32 length and the contents, closed by an empty end field. The public key is required;
+/// the secret key, ciphertext and shared secret are only present once known, and the negotiation transcript only if the
+/// algorithm was negotiated. Readers skip fields they do not recognize unless the tag's high bit is set
+///
+/// With the `serde` feature, this implements `Serialize` and `Deserialize` without the secret key and shared secret,
+/// which are always written as absent and dropped when read. [crate::hazmat::SecretExport] includes them
 pub struct PostQuantumExport {
     pub(super) algorithm: u8,
+    pub(super) aead_suite: u8,
+    pub(super) node: PQNode,
+    pub(super) in_counter: u64,
+    pub(super) out_counter: u64,
     pub(super) public_key: Vec<u8>,
     pub(super) secret_key: Option<Vec<u8>>,
     pub(super) ciphertext: Option<Vec<u8>>,
     pub(super) shared_secret: Option<Vec<u8>>,
-    pub(super) node: u8
+    pub(super) negotiation: Option<Vec<u8>>
+}
+
+/// The serde layout of a [PostQuantumExport], borrowing its fields
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct SerdeExportRef<'a> {
+    algorithm: u8,
+    aead_suite: u8,
+    node: PQNode,
+    in_counter: u64,
+    out_counter: u64,
+    #[serde(serialize_with = "serde_bytes_field::serialize")]
+    public_key: &'a [u8],
+    #[serde(serialize_with = "serde_optional_bytes_field::serialize")]
+    secret_key: Option<&'a [u8]>,
+    #[serde(serialize_with = "serde_optional_bytes_field::serialize")]
+    ciphertext: Option<&'a [u8]>,
+    #[serde(serialize_with = "serde_optional_bytes_field::serialize")]
+    shared_secret: Option<&'a [u8]>,
+    #[serde(serialize_with = "serde_optional_bytes_field::serialize")]
+    negotiation: Option<&'a [u8]>
+}
+
+/// The serde layout of a [PostQuantumExport]
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerdeExport {
+    algorithm: u8,
+    aead_suite: u8,
+    node: PQNode,
+    in_counter: u64,
+    out_counter: u64,
+    #[serde(deserialize_with = "serde_bytes_field::deserialize")]
+    public_key: Vec<u8>,
+    #[serde(deserialize_with = "serde_optional_bytes_field::deserialize")]
+    secret_key: Option<Vec<u8>>,
+    #[serde(deserialize_with = "serde_optional_bytes_field::deserialize")]
+    ciphertext: Option<Vec<u8>>,
+    #[serde(deserialize_with = "serde_optional_bytes_field::deserialize")]
+    shared_secret: Option<Vec<u8>>,
+    #[serde(default, deserialize_with = "serde_optional_bytes_field::deserialize")]
+    negotiation: Option<Vec<u8>>
+}
+
+#[cfg(feature = "serde")]
+impl PostQuantumExport {
+    /// Serializes the export, leaving out the secret key and shared secret unless `with_secrets` is set
+    pub(crate) fn serialize_with<S: serde::Serializer>(&self, with_secrets: bool, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::Serialize;
+
+        SerdeExportRef {
+            algorithm: self.algorithm,
+            aead_suite: self.aead_suite,
+            node: self.node,
+            in_counter: self.in_counter,
+            out_counter: self.out_counter,
+            public_key: &self.public_key,
+            secret_key: self.secret_key.as_deref().filter(|_| with_secrets),
+            ciphertext: self.ciphertext.as_deref(),
+            shared_secret: self.shared_secret.as_deref().filter(|_| with_secrets),
+            negotiation: self.negotiation.as_deref()
+        }.serialize(serializer)
+    }
+
+    /// Deserializes an export, zeroing and dropping the secret key and shared secret unless `with_secrets` is set
+    pub(crate) fn deserialize_with<'de, D: serde::Deserializer<'de>>(with_secrets: bool, deserializer: D) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        let fields = SerdeExport::deserialize(deserializer)?;
+        let mut export = Self {
+            algorithm: fields.algorithm,
+            aead_suite: fields.aead_suite,
+            node: fields.node,
+            in_counter: fields.in_counter,
+            out_counter: fields.out_counter,
+            public_key: fields.public_key,
+            secret_key: fields.secret_key,
+            ciphertext: fields.ciphertext,
+            shared_secret: fields.shared_secret,
+            negotiation: fields.negotiation
+        };
+
+        if !with_secrets {
+            export.zeroize();
+        }
+
+        Ok(export)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PostQuantumExport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_with(false, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PostQuantumExport {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize_with(false, deserializer)
+    }
+}
+
+/// Byte fields are base64 strings in human-readable formats and raw bytes in binary ones
+#[cfg(feature = "serde")]
+mod serde_bytes_field {
+    pub use pqcrypto::traits::serialization::{deserialize_bytes as deserialize, serialize_bytes as serialize};
+}
+
+#[cfg(feature = "serde")]
+mod serde_optional_bytes_field {
+    use alloc::vec::Vec;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct BytesRef<'a>(&'a [u8]);
+
+    impl Serialize for BytesRef<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serde_bytes_field::serialize(self.0, serializer)
+        }
+    }
+
+    struct Bytes(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for Bytes {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            super::serde_bytes_field::deserialize(deserializer).map(Bytes)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &Option<&[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes.map(BytesRef).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Ok(Option::<Bytes>::deserialize(deserializer)?.map(|bytes| bytes.0))
+    }
 }
 
 impl From<&'_ PostQuantumContainer> for PostQuantumExport {
     fn from(container: &PostQuantumContainer) -> Self {
         let algorithm = container.algorithm;
-        let node = if container.node == PQNode::Alice {
-            0u8
-        } else {
-            1u8
-        };
+        let node = container.get_node_type();
+        let (in_counter, out_counter) = container.data.get_counters();
 
         let public_key = container.get_public_key().to_vec();
-        let secret_key = {
-            if let Ok(secret_key) = container.get_secret_key() {
-                Some(secret_key.to_vec())
-            } else {
-                None
-            }
-        };
+        let secret_key = container.get_secret_key().ok().map(|secret_key| secret_key.to_vec());
+        let ciphertext = container.get_ciphertext().ok().map(|ciphertext| ciphertext.to_vec());
+        let shared_secret = container.get_shared_secret().ok().map(|shared_secret| shared_secret.to_vec());
+        let negotiation = container.data.get_negotiation().map(|transcript| transcript.to_vec());
 
-        let ciphertext = {
-            if let Ok(ciphertext) = container.get_ciphertext() {
-                Some(ciphertext.to_vec())
-            } else {
-                None
-            }
-        };
+        Self { algorithm, aead_suite: AEAD_SUITE, node, in_counter, out_counter, public_key, secret_key, ciphertext, shared_secret, negotiation }
+    }
+}
+
+impl PostQuantumExport {
+    /// The tag and contents of each field that is present, in serialization order
+    fn fields(&self) -> impl Iterator<Item=(u8, &[u8])> {
+        core::iter::once(Some((FIELD_PUBLIC_KEY, self.public_key.as_slice())))
+            .chain(core::iter::once(self.secret_key.as_deref().map(|bytes| (FIELD_SECRET_KEY, bytes))))
+            .chain(core::iter::once(self.ciphertext.as_deref().map(|bytes| (FIELD_CIPHERTEXT, bytes))))
+            .chain(core::iter::once(self.shared_secret.as_deref().map(|bytes| (FIELD_SHARED_SECRET, bytes))))
+            .chain(core::iter::once(self.negotiation.as_deref().map(|bytes| (FIELD_NEGOTIATION, bytes))))
+            .flatten()
+    }
+
+    /// Serializes to the current format version. The output holds the secrets, so it is zeroed when dropped
+    pub(crate) fn encode(&self) -> Zeroizing<Vec<u8>> {
+        let len = HEADER_LENGTH + self.fields().map(|(_, bytes)| FIELD_HEADER_LENGTH + bytes.len()).sum::<usize>() + FIELD_HEADER_LENGTH;
+        // reserve everything up front so that the output never reallocates, which would leave copies of the secrets behind
+        let mut output = Zeroizing::new(Vec::with_capacity(len));
+        output.extend_from_slice(&EXPORT_MAGIC);
+        output.push(EXPORT_FORMAT_VERSION);
+        output.push(self.algorithm);
+        output.push(self.aead_suite);
+        output.push(if self.node == PQNode::Alice { 0 } else { 1 });
+        output.extend_from_slice(&self.in_counter.to_be_bytes());
+        output.extend_from_slice(&self.out_counter.to_be_bytes());
+
+        for (tag, bytes) in self.fields() {
+            output.push(tag);
+            output.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            output.extend_from_slice(bytes);
+        }
+
+        output.push(FIELD_END);
+        output.extend_from_slice(&0u32.to_be_bytes());
+        output
+    }
+
+    /// Deserializes any supported format version. Input without [EXPORT_MAGIC] is read as the original, unversioned format
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, EzError> {
+        if bytes.starts_with(&EXPORT_MAGIC) {
+            Self::decode_versioned(bytes)
+        } else {
+            Self::decode_legacy(bytes)
+        }
+    }
 
-        let shared_secret = {
-            if let Ok(shared_secret) = container.get_shared_secret() {
-                Some(shared_secret.to_vec())
-            } else {
-                None
+    #[cfg(feature = "std")]
+    fn decode_legacy(bytes: &[u8]) -> Result<Self, EzError> {
+        LegacyExport::deserialize_bin(bytes).map_err(EzError::deserialize)?.into_export()
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn decode_legacy(_bytes: &[u8]) -> Result<Self, EzError> {
+        Err(EzError::InvalidInput("reading unversioned containers requires the std feature"))
+    }
+
+    fn decode_versioned(bytes: &[u8]) -> Result<Self, EzError> {
+        let mut reader = Reader { bytes, position: EXPORT_MAGIC.len() };
+        let version = reader.u8()?;
+        if version != EXPORT_FORMAT_VERSION {
+            return Err(EzError::UnsupportedVersion(version));
+        }
+
+        let algorithm = reader.u8()?;
+        let aead_suite = reader.u8()?;
+        let node = node_from_byte(reader.u8()?)?;
+        let in_counter = reader.u64()?;
+        let out_counter = reader.u64()?;
+
+        let mut export = Self { algorithm, aead_suite, node, in_counter, out_counter, public_key: Vec::new(), secret_key: None, ciphertext: None, shared_secret: None, negotiation: None };
+        let mut has_public_key = false;
+
+        loop {
+            let tag = reader.u8()?;
+            let len = reader.u32()? as usize;
+            let contents = reader.take(len)?;
+
+            let slot = match tag {
+                FIELD_END => break,
+                FIELD_PUBLIC_KEY if !has_public_key => {
+                    has_public_key = true;
+                    export.public_key = contents.to_vec();
+                    continue;
+                },
+                FIELD_SECRET_KEY => &mut export.secret_key,
+                FIELD_CIPHERTEXT => &mut export.ciphertext,
+                FIELD_SHARED_SECRET => &mut export.shared_secret,
+                FIELD_NEGOTIATION => &mut export.negotiation,
+                FIELD_PUBLIC_KEY => return Err(EzError::InvalidInput("duplicate field in serialized container")),
+                tag if tag & FIELD_CRITICAL != 0 => return Err(EzError::InvalidInput("unknown critical field in serialized container")),
+                _ => continue
+            };
+
+            if slot.is_some() {
+                return Err(EzError::InvalidInput("duplicate field in serialized container"));
             }
-        };
 
-        Self { algorithm, public_key, secret_key, ciphertext, shared_secret, node }
+            *slot = Some(contents.to_vec());
+        }
+
+        if !reader.is_empty() {
+            return Err(EzError::InvalidInput("trailing data after serialized container"));
+        }
+
+        if !has_public_key {
+            return Err(EzError::InvalidInput("serialized container is missing the public key"));
+        }
+
+        Ok(export)
     }
-}
\ No newline at end of file
+}
+
+fn node_from_byte(node: u8) -> Result<PQNode, EzError> {
+    match node {
+        0 => Ok(PQNode::Alice),
+        1 => Ok(PQNode::Bob),
+        _ => Err(EzError::InvalidInput("unknown node type"))
+    }
+}
+
+/// A bounds-checked cursor over the serialized bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], EzError> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or(EzError::InvalidLength { what: "serialized container", expected: self.position.saturating_add(len), actual: self.bytes.len() })?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, EzError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EzError> {
+        let mut array = [0u8; 4];
+        array.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(array))
+    }
+
+    fn u64(&mut self) -> Result<u64, EzError> {
+        let mut array = [0u8; 8];
+        array.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(array))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
+
+/// The original, unversioned nanoserde layout. It is only ever read, so that containers stored before
+/// [EXPORT_FORMAT_VERSION] 1 can still be loaded
+#[cfg(feature = "std")]
+struct LegacyExport {
+    algorithm: u8,
+    public_key: Vec<u8>,
+    secret_key: Option<Vec<u8>>,
+    ciphertext: Option<Vec<u8>>,
+    shared_secret: Option<Vec<u8>>,
+    node: u8
+}
+
+#[cfg(feature = "std")]
+impl DeBin for LegacyExport {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(Self {
+            algorithm: DeBin::de_bin(o, d)?,
+            public_key: de_bytes(o, d)?,
+            secret_key: de_optional_bytes(o, d)?,
+            ciphertext: de_optional_bytes(o, d)?,
+            shared_secret: de_optional_bytes(o, d)?,
+            node: DeBin::de_bin(o, d)?
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl LegacyExport {
+    /// The legacy format predates the AEAD suite and replay state, so it is assumed to use this build's suite and fresh counters
+    fn into_export(mut self) -> Result<PostQuantumExport, EzError> {
+        Ok(PostQuantumExport {
+            algorithm: self.algorithm,
+            aead_suite: AEAD_SUITE,
+            node: node_from_byte(self.node)?,
+            in_counter: 0,
+            out_counter: 0,
+            public_key: core::mem::take(&mut self.public_key),
+            secret_key: self.secret_key.take(),
+            ciphertext: self.ciphertext.take(),
+            shared_secret: self.shared_secret.take(),
+            negotiation: None
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for LegacyExport {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+        self.shared_secret.zeroize();
+    }
+}
+
+/// Reads a length prefix, rejecting it if the rest of the input cannot hold `len` elements of at least
+/// `min_element_len` bytes each. nanoserde sizes a vector's allocation from the prefix before reading any
+/// elements, so an unchecked prefix lets hostile input request an arbitrarily large allocation
+#[cfg(feature = "std")]
+pub(crate) fn de_bounded_len(o: &mut usize, d: &[u8], min_element_len: usize) -> Result<usize, DeBinErr> {
+    let start = *o;
+    let len: u64 = DeBin::de_bin(o, d)?;
+    let err = DeBinErr { o: start, l: 8, s: d.len() };
+    let len = usize::try_from(len).map_err(|_| err.clone())?;
+
+    match len.checked_mul(min_element_len) {
+        Some(required) if required <= d.len().saturating_sub(*o) => Ok(len),
+        _ => Err(err)
+    }
+}
+
+/// Reads a length-prefixed byte vector
+#[cfg(feature = "std")]
+pub(crate) fn de_bytes(o: &mut usize, d: &[u8]) -> Result<Vec<u8>, DeBinErr> {
+    let len = de_bounded_len(o, d, 1)?;
+    let bytes = d[*o..*o + len].to_vec();
+    *o += len;
+    Ok(bytes)
+}
+
+/// Reads an optional length-prefixed byte vector
+#[cfg(feature = "std")]
+pub(crate) fn de_optional_bytes(o: &mut usize, d: &[u8]) -> Result<Option<Vec<u8>>, DeBinErr> {
+    if u8::de_bin(o, d)? == 1 {
+        de_bytes(o, d).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+impl core::fmt::Debug for PostQuantumExport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PostQuantumExport")
+            .field("algorithm", &self.algorithm)
+            .field("aead_suite", &self.aead_suite)
+            .field("node", &self.node)
+            .field("secret_key", &self.secret_key.as_ref().map(|_| "<redacted>"))
+            .field("shared_secret", &self.shared_secret.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Zeroize for PostQuantumExport {
+    fn zeroize(&mut self) {
+        self.secret_key.zeroize();
+        self.shared_secret.zeroize();
+    }
+}
+
+impl Drop for PostQuantumExport {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PostQuantumExport {}
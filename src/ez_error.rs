@@ -1,23 +1,117 @@
+use crate::PQNode;
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+
 /// The default error type for this crate
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum EzError {
-    /// The shared secret is not loaded
-    SharedSecretNotLoaded,
+    /// The key exchange has not progressed far enough for the requested operation (e.g., the
+    /// shared secret or ciphertext is not yet available)
+    HandshakeIncomplete {
+        /// The piece of state that is missing
+        missing: &'static str
+    },
+    /// The operation is not valid for this node's role in the exchange
+    WrongRole {
+        /// The role of the local node
+        role: PQNode,
+        /// The operation that was attempted
+        operation: &'static str
+    },
+    /// An input had the wrong length
+    InvalidLength {
+        /// What the input was
+        what: &'static str,
+        /// The length that was expected
+        expected: usize,
+        /// The length that was received
+        actual: usize
+    },
+    /// The output buffer does not have enough room
+    InsufficientCapacity {
+        /// The number of bytes needed
+        required: usize,
+        /// The number of bytes available
+        available: usize
+    },
+    /// The algorithm byte does not correspond to an algorithm supported by this build
+    UnsupportedAlgorithm(u8),
+    /// The serialized data uses a format version this build cannot read
+    UnsupportedVersion(u8),
+    /// The serialized data was produced with an AEAD suite other than the one compiled into this build
+    UnsupportedAeadSuite(u8),
+    /// The packet was already received, or is outside the accepted window
+    ReplayDetected,
     /// Failed to encrypt the data
-    AesGcmEncryptionFailure,
-    /// Failed to decrypt the data
-    AesGcmDecryptionFailure,
-    /// For all other error types
-    Generic(&'static str)
+    EncryptionFailure,
+    /// The ciphertext, tag or associated data failed to authenticate
+    AuthenticationFailed,
+    /// The input bytes could not be deserialized
+    DeserializeError {
+        /// The underlying decoding error
+        #[cfg(feature = "std")]
+        source: Box<dyn std::error::Error + Send + Sync>
+    },
+    /// An argument was invalid for a reason other than its length
+    InvalidInput(&'static str),
+    /// A counter or length limit was reached
+    LimitExceeded(&'static str),
+    /// The peers could not agree on an algorithm, or the peer's choice was not the one the offers dictate
+    NegotiationFailed(&'static str),
+    /// The algorithm is not permitted by the local [crate::negotiation::Policy]
+    PolicyViolation(u8),
+    /// An error from the underlying post-quantum primitive
+    Primitive(pqcrypto::traits::Error)
+}
+
+#[cfg(feature = "std")]
+impl EzError {
+    /// Wraps a decoding error
+    pub(crate) fn deserialize<E: std::error::Error + Send + Sync + 'static>(source: E) -> Self {
+        EzError::DeserializeError { source: Box::new(source) }
+    }
 }
 
-impl ToString for EzError {
-    fn to_string(&self) -> String {
-        match *self {
-            EzError::SharedSecretNotLoaded => "Shared secret not loaded".to_string(),
-            EzError::AesGcmEncryptionFailure => "AES-GCM Encryption Failure".to_string(),
-            EzError::AesGcmDecryptionFailure => "AES-GCM Decryption Failure".to_string(),
-            EzError::Generic(val) => val.to_string()
+impl core::fmt::Display for EzError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EzError::HandshakeIncomplete { missing } => write!(f, "Handshake incomplete: {} not available", missing),
+            EzError::WrongRole { role, operation } => write!(f, "{} is not valid for {:?}", operation, role),
+            EzError::InvalidLength { what, expected, actual } => write!(f, "Invalid {} length: expected {} bytes, got {}", what, expected, actual),
+            EzError::InsufficientCapacity { required, available } => write!(f, "Insufficient capacity: {} bytes required, {} available", required, available),
+            EzError::UnsupportedAlgorithm(algorithm) => write!(f, "Unsupported algorithm: {}", algorithm),
+            EzError::UnsupportedVersion(version) => write!(f, "Unsupported format version: {}", version),
+            EzError::UnsupportedAeadSuite(suite) => write!(f, "Unsupported AEAD suite: {}", suite),
+            EzError::ReplayDetected => write!(f, "Anti-replay-attack: invalid packet ID"),
+            EzError::EncryptionFailure => write!(f, "AEAD encryption failure"),
+            EzError::AuthenticationFailed => write!(f, "AEAD authentication failure"),
+            EzError::DeserializeError { .. } => write!(f, "Deserialization failure"),
+            EzError::InvalidInput(val) => write!(f, "Invalid input: {}", val),
+            EzError::LimitExceeded(val) => write!(f, "Limit exceeded: {}", val),
+            EzError::NegotiationFailed(val) => write!(f, "Negotiation failed: {}", val),
+            EzError::PolicyViolation(algorithm) => write!(f, "Algorithm {} is not permitted by the policy", algorithm),
+            EzError::Primitive(_) => write!(f, "Post-quantum primitive error")
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EzError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EzError::DeserializeError { source } => Some(source.as_ref()),
+            EzError::Primitive(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<pqcrypto::traits::Error> for EzError {
+    fn from(err: pqcrypto::traits::Error) -> Self {
+        match err {
+            pqcrypto::traits::Error::BadLength { name, actual, expected } => EzError::InvalidLength { what: name, expected, actual },
+            err => EzError::Primitive(err)
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::ez_error::EzError;
+#[cfg(feature = "serde")]
+use crate::export::PostQuantumExport;
+use crate::PostQuantumContainer;
+
+/// Raw access to the secret key and shared secret of a [PostQuantumContainer].
+///
+/// These bytes must never be logged or sent anywhere. Prefer the higher-level operations on the
+/// container ([PostQuantumContainer::key_confirmation], [PostQuantumContainer::export_keying_material],
+/// [PostQuantumContainer::shared_secret_eq]) where possible. Import this trait to opt in
+pub trait SecretAccess {
+    /// Gets the secret key (If node is Alice type)
+    fn get_secret_key(&self) -> Result<&[u8], EzError>;
+    /// Gets the shared secret
+    fn get_shared_secret(&self) -> Result<&[u8], EzError>;
+}
+
+impl SecretAccess for PostQuantumContainer {
+    fn get_secret_key(&self) -> Result<&[u8], EzError> {
+        PostQuantumContainer::get_secret_key(self)
+    }
+
+    fn get_shared_secret(&self) -> Result<&[u8], EzError> {
+        PostQuantumContainer::get_shared_secret(self)
+    }
+}
+
+/// A [PostQuantumExport] whose serde implementations include the secret key and shared secret, which
+/// [PostQuantumExport]'s own implementations leave out. The serialized output must be protected like the secrets
+#[cfg(feature = "serde")]
+pub struct SecretExport(pub PostQuantumExport);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SecretExport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_with(true, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SecretExport {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PostQuantumExport::deserialize_with(true, deserializer).map(SecretExport)
+    }
+}
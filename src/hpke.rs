@@ -0,0 +1,244 @@
+use alloc::vec::Vec;
+use alloc::vec;
+use hkdf::{Hkdf, HkdfExtract};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+#[cfg(feature = "chacha20")]
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+#[cfg(not(feature = "chacha20"))]
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+use crate::{PostQuantumContainer, NONCE_LENGTH_BYTES};
+use crate::ez_error::EzError;
+
+/// The mode used when only the recipient's public key authenticates the exchange
+pub const MODE_BASE: u8 = 0x00;
+/// The mode used when both sides additionally hold a pre-shared key
+pub const MODE_PSK: u8 = 0x01;
+/// HKDF-SHA256
+pub const KDF_ID: u16 = 0x0001;
+// The PQ KEMs and the AEADs used by this crate are not registered with IANA, so the KEM and AEAD
+// identifiers are private-use values. Peers must agree on them out of band
+/// The private-use AEAD identifier for the compiled-in cipher suite
+#[cfg(feature = "chacha20")]
+pub const AEAD_ID: u16 = 0xFF02;
+/// The private-use AEAD identifier for the compiled-in cipher suite
+#[cfg(not(feature = "chacha20"))]
+pub const AEAD_ID: u16 = 0xFF01;
+
+const HPKE_VERSION_LABEL: &[u8] = b"HPKE-v1";
+/// Nk
+const KEY_LENGTH: usize = 32;
+/// Nh
+const HASH_LENGTH: usize = 32;
+/// Nsecret
+const SECRET_LENGTH: usize = 32;
+
+/// Returns the private-use KEM identifier for `algorithm`
+pub fn kem_id(algorithm: u8) -> u16 {
+    0xFF00 | algorithm as u16
+}
+
+fn kem_suite_id(algorithm: u8) -> Vec<u8> {
+    let mut suite_id = b"KEM".to_vec();
+    suite_id.extend_from_slice(&kem_id(algorithm).to_be_bytes());
+    suite_id
+}
+
+fn hpke_suite_id(algorithm: u8) -> Vec<u8> {
+    let mut suite_id = b"HPKE".to_vec();
+    suite_id.extend_from_slice(&kem_id(algorithm).to_be_bytes());
+    suite_id.extend_from_slice(&KDF_ID.to_be_bytes());
+    suite_id.extend_from_slice(&AEAD_ID.to_be_bytes());
+    suite_id
+}
+
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut extract = HkdfExtract::<Sha256>::new(Some(salt));
+    extract.input_ikm(HPKE_VERSION_LABEL);
+    extract.input_ikm(suite_id);
+    extract.input_ikm(label);
+    extract.input_ikm(ikm);
+    Zeroizing::new(extract.finalize().0.to_vec())
+}
+
+fn labeled_expand(suite_id: &[u8], prk: &[u8], label: &[u8], info: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+    if len > u16::MAX as usize {
+        return Err(EzError::LimitExceeded("HPKE expansion length too large"));
+    }
+
+    let hkdf = Hkdf::<Sha256>::from_prk(prk).map_err(|_| EzError::InvalidLength { what: "HPKE pseudorandom key", expected: HASH_LENGTH, actual: prk.len() })?;
+    let mut okm = Zeroizing::new(vec![0u8; len]);
+    hkdf.expand_multi_info(&[&(len as u16).to_be_bytes(), HPKE_VERSION_LABEL, suite_id, label, info], &mut okm).map_err(|_| EzError::LimitExceeded("HPKE expansion length too large"))?;
+    Ok(okm)
+}
+
+/// Binds the raw KEM output to the encapsulation and the recipient's public key
+fn extract_and_expand(algorithm: u8, kem_shared_secret: &[u8], enc: &[u8], public_key: &[u8]) -> Result<Zeroizing<Vec<u8>>, EzError> {
+    let suite_id = kem_suite_id(algorithm);
+    let mut kem_context = Vec::with_capacity(enc.len() + public_key.len());
+    kem_context.extend_from_slice(enc);
+    kem_context.extend_from_slice(public_key);
+
+    let eae_prk = labeled_extract(&suite_id, &[], b"eae_prk", kem_shared_secret);
+    labeled_expand(&suite_id, &eae_prk, b"shared_secret", &kem_context, SECRET_LENGTH)
+}
+
+fn key_schedule(algorithm: u8, mode: u8, shared_secret: &[u8], info: &[u8], psk: &[u8], psk_id: &[u8]) -> Result<Context, EzError> {
+    match mode {
+        MODE_BASE if !psk.is_empty() || !psk_id.is_empty() => return Err(EzError::InvalidInput("PSK input provided when not needed")),
+        MODE_PSK if psk.is_empty() || psk_id.is_empty() => return Err(EzError::InvalidInput("missing required PSK input")),
+        MODE_BASE | MODE_PSK => {},
+        _ => return Err(EzError::InvalidInput("unsupported HPKE mode"))
+    }
+
+    let suite_id = hpke_suite_id(algorithm);
+    let psk_id_hash = labeled_extract(&suite_id, &[], b"psk_id_hash", psk_id);
+    let info_hash = labeled_extract(&suite_id, &[], b"info_hash", info);
+    let mut key_schedule_context = vec![mode];
+    key_schedule_context.extend_from_slice(&psk_id_hash);
+    key_schedule_context.extend_from_slice(&info_hash);
+
+    let secret = labeled_extract(&suite_id, shared_secret, b"secret", psk);
+    let key = labeled_expand(&suite_id, &secret, b"key", &key_schedule_context, KEY_LENGTH)?;
+    let base_nonce = labeled_expand(&suite_id, &secret, b"base_nonce", &key_schedule_context, NONCE_LENGTH_BYTES)?;
+    let exporter_secret = labeled_expand(&suite_id, &secret, b"exp", &key_schedule_context, HASH_LENGTH)?;
+
+    let key = AeadKey::new(GenericArray::from_slice(&key));
+    let mut nonce = [0u8; NONCE_LENGTH_BYTES];
+    nonce.copy_from_slice(&base_nonce);
+
+    Ok(Context { suite_id, key, base_nonce: nonce, sequence_number: 0, exporter_secret })
+}
+
+fn encap(algorithm: u8, public_key: &[u8]) -> Result<(Zeroizing<Vec<u8>>, Vec<u8>), EzError> {
+    crate::check_backend(algorithm)?;
+
+    let bob_container = PostQuantumContainer::new_bob(algorithm, public_key)?;
+    let enc = bob_container.get_ciphertext()?.to_vec();
+    let kem_shared_secret = bob_container.get_shared_secret().map_err(|_err| EzError::HandshakeIncomplete { missing: "shared secret" })?;
+    let shared_secret = extract_and_expand(algorithm, kem_shared_secret, &enc, public_key)?;
+    Ok((shared_secret, enc))
+}
+
+fn decap(algorithm: u8, enc: &[u8], secret_key: &[u8], public_key: &[u8]) -> Result<Zeroizing<Vec<u8>>, EzError> {
+    crate::check_backend(algorithm)?;
+
+    let kem_shared_secret = PostQuantumContainer::get_decapsulation(algorithm, secret_key, enc)?;
+    extract_and_expand(algorithm, &kem_shared_secret, enc, public_key)
+}
+
+/// Sets up a sender context in base mode. Returns the encapsulation, `enc`, which must be sent to the recipient
+pub fn setup_base_s(algorithm: u8, recipient_public_key: &[u8], info: &[u8]) -> Result<(Vec<u8>, SenderContext), EzError> {
+    let (shared_secret, enc) = encap(algorithm, recipient_public_key)?;
+    let context = key_schedule(algorithm, MODE_BASE, &shared_secret, info, &[], &[])?;
+    Ok((enc, SenderContext { context }))
+}
+
+/// Sets up a recipient context in base mode from the sender's `enc`
+pub fn setup_base_r(algorithm: u8, enc: &[u8], recipient_secret_key: &[u8], recipient_public_key: &[u8], info: &[u8]) -> Result<ReceiverContext, EzError> {
+    let shared_secret = decap(algorithm, enc, recipient_secret_key, recipient_public_key)?;
+    let context = key_schedule(algorithm, MODE_BASE, &shared_secret, info, &[], &[])?;
+    Ok(ReceiverContext { context })
+}
+
+/// Sets up a sender context in PSK mode. Returns the encapsulation, `enc`, which must be sent to the recipient
+pub fn setup_psk_s(algorithm: u8, recipient_public_key: &[u8], info: &[u8], psk: &[u8], psk_id: &[u8]) -> Result<(Vec<u8>, SenderContext), EzError> {
+    let (shared_secret, enc) = encap(algorithm, recipient_public_key)?;
+    let context = key_schedule(algorithm, MODE_PSK, &shared_secret, info, psk, psk_id)?;
+    Ok((enc, SenderContext { context }))
+}
+
+/// Sets up a recipient context in PSK mode from the sender's `enc`
+pub fn setup_psk_r(algorithm: u8, enc: &[u8], recipient_secret_key: &[u8], recipient_public_key: &[u8], info: &[u8], psk: &[u8], psk_id: &[u8]) -> Result<ReceiverContext, EzError> {
+    let shared_secret = decap(algorithm, enc, recipient_secret_key, recipient_public_key)?;
+    let context = key_schedule(algorithm, MODE_PSK, &shared_secret, info, psk, psk_id)?;
+    Ok(ReceiverContext { context })
+}
+
+struct Context {
+    suite_id: Vec<u8>,
+    key: AeadKey,
+    base_nonce: [u8; NONCE_LENGTH_BYTES],
+    sequence_number: u64,
+    exporter_secret: Zeroizing<Vec<u8>>
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        crate::wipe_aead_key(&mut self.key);
+    }
+}
+
+impl Context {
+    /// XORs the big-endian sequence number into the low-order bytes of the base nonce
+    fn compute_nonce(&self) -> [u8; NONCE_LENGTH_BYTES] {
+        let mut nonce = self.base_nonce;
+        let seq = self.sequence_number.to_be_bytes();
+        for (byte, seq_byte) in nonce[NONCE_LENGTH_BYTES - seq.len()..].iter_mut().zip(seq.iter()) {
+            *byte ^= *seq_byte;
+        }
+
+        nonce
+    }
+
+    fn increment_sequence_number(&mut self) -> Result<(), EzError> {
+        self.sequence_number = self.sequence_number.checked_add(1).ok_or(EzError::LimitExceeded("HPKE message limit reached"))?;
+        Ok(())
+    }
+
+    fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        labeled_expand(&self.suite_id, &self.exporter_secret, b"sec", exporter_context, len)
+    }
+}
+
+/// The sender's half of an HPKE context
+pub struct SenderContext {
+    context: Context
+}
+
+impl core::fmt::Debug for SenderContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SenderContext").field("sequence_number", &self.context.sequence_number).finish_non_exhaustive()
+    }
+}
+
+impl SenderContext {
+    /// Encrypts `plaintext`, authenticating `aad`. Messages must be opened in the order they were sealed
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EzError> {
+        let nonce = self.context.compute_nonce();
+        let ciphertext = self.context.key.encrypt(GenericArray::from_slice(&nonce), Payload { msg: plaintext, aad }).map_err(|_| EzError::EncryptionFailure)?;
+        self.context.increment_sequence_number()?;
+        Ok(ciphertext)
+    }
+
+    /// Derives a secret of `len` bytes bound to this context and `exporter_context`. The secret is wiped on drop
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        self.context.export(exporter_context, len)
+    }
+}
+
+/// The recipient's half of an HPKE context
+pub struct ReceiverContext {
+    context: Context
+}
+
+impl core::fmt::Debug for ReceiverContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReceiverContext").field("sequence_number", &self.context.sequence_number).finish_non_exhaustive()
+    }
+}
+
+impl ReceiverContext {
+    /// Decrypts `ciphertext`, verifying `aad`. A failed attempt does not advance the sequence number
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EzError> {
+        let nonce = self.context.compute_nonce();
+        let plaintext = self.context.key.decrypt(GenericArray::from_slice(&nonce), Payload { msg: ciphertext, aad }).map_err(|_| EzError::AuthenticationFailed)?;
+        self.context.increment_sequence_number()?;
+        Ok(plaintext)
+    }
+
+    /// Derives a secret of `len` bytes bound to this context and `exporter_context`. The secret is wiped on drop
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        self.context.export(exporter_context, len)
+    }
+}
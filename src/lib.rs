@@ -1,23 +1,33 @@
-#![allow(non_camel_case_types)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 use crate::algorithm_dictionary::*;
-use rand::Rng;
+use rand::{CryptoRng, Rng, RngCore};
 use crate::export::PostQuantumExport;
-use pqcrypto::traits::Error;
-use std::convert::TryFrom;
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+use zeroize::Zeroizing;
+use subtle::ConstantTimeEq;
+use sha2::{Digest, Sha256};
 #[cfg(feature = "chacha20")]
-use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{NewAead, Aead, AeadInPlace, generic_array::GenericArray}};
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::generic_array::{ArrayLength, GenericArray, typenum::U16}};
 #[cfg(not(feature = "chacha20"))]
-use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{NewAead, Aead, AeadInPlace, generic_array::GenericArray}};
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::generic_array::{ArrayLength, GenericArray, typenum::U16}};
 use crate::ez_error::EzError;
-use nanoserde::{SerBin, DeBin};
-use crate::bytes_in_place::InPlaceBytesMut;
-use bytes::{BytesMut, BufMut};
-use crate::replay_attack_container::ordered::AntiReplayAttackContainerOrdered;
+use crate::negotiation::{Offer, Policy};
+use bytes::BytesMut;
 
 pub mod prelude {
-    pub use pqcrypto::traits::Error;
-    pub use crate::{PQNode, PostQuantumContainer, PostQuantumType, algorithm_dictionary};
+    pub use crate::ez_error::EzError;
+    pub use crate::container::{Algorithm, Container, Kem};
+    pub use crate::negotiation::{Offer, Policy};
+    pub use crate::{PQNode, PostQuantumContainer, algorithm_dictionary};
 }
 
 #[cfg(feature = "chacha20")]
@@ -26,7 +36,26 @@ pub const NONCE_LENGTH_BYTES: usize = 24;
 #[cfg(not(feature = "chacha20"))]
 pub const NONCE_LENGTH_BYTES: usize = 12;
 
+/// The number of bytes a protected packet grows by: the 8-byte PID plus the 16-byte tag
+pub const PACKET_OVERHEAD_BYTES: usize = 8 + 16;
+
+/// The length of the seed accepted by [PostQuantumContainer::new_alice_from_seed]
+pub const SEED_LENGTH_BYTES: usize = pqcrypto::kem::firesaber::SEED_BYTES;
+
+/// The most keying material [PostQuantumContainer::export_keying_material] can derive (the HKDF-SHA256 limit)
+pub const MAX_EXPORT_LENGTH: usize = 255 * 32;
+
+/// A cryptographically secure [RngCore], usable as a trait object. Implemented for every [CryptoRng]
+pub trait CryptoRngCore: RngCore + CryptoRng {}
+
+impl<R: RngCore + CryptoRng + ?Sized> CryptoRngCore for R {}
+
+/// The detached authentication tag produced by the AEAD
+pub type Tag = GenericArray<u8, U16>;
+
 pub mod bytes_in_place;
+/// A statically dispatched container over a single KEM and AEAD
+pub mod container;
 /// For handling serialization/deserialization
 pub mod export;
 
@@ -35,17 +64,34 @@ pub mod ez_error;
 
 pub mod replay_attack_container;
 
-/// Contains the public keys for Alice and Bob
+/// For sealing data to a recipient's public key without an interactive exchange
+#[cfg(feature = "std")]
+pub mod envelope;
+/// Opt-in access to raw secret material
+pub mod hazmat;
+
+/// Password-based encryption of serialized containers
+pub mod password;
+
+/// An HPKE (RFC 9180) style API using the post-quantum KEMs as the KEM component
+pub mod hpke;
+
+/// For agreeing on an algorithm with the other side before the exchange
+pub mod negotiation;
+
+/// For encrypting and decrypting payloads too large to hold in memory
+#[cfg(feature = "std")]
+pub mod stream;
+
+/// Contains the public keys for Alice and Bob. This is the dynamic, byte-selected wrapper around [container::Container]
 pub struct PostQuantumContainer {
     pub(crate) algorithm: u8,
-    pub(crate) data: Box<dyn PostQuantumType>,
-    pub(crate) anti_replay_attack: AntiReplayAttackContainerOrdered,
-    pub(crate) aes_gcm_key: Option<AeadKey>,
-    pub(crate) node: PQNode
+    pub(crate) data: Box<dyn PostQuantumType>
 }
 
 /// Used to denote the local node's instance type
 #[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PQNode {
     /// The first node in the exchange. Alice generates a key, gets a public key (pk)
     /// and a secret key (sk). Alice sends pk to Bob
@@ -59,84 +105,189 @@ pub enum PQNode {
 
 
 impl PostQuantumContainer {
-    /// Creates a new [PostQuantumContainer] for Alice. This will panic if the algorithm is
-    /// invalid
+    /// Creates a new [PostQuantumContainer] for Alice. Returns an error if the algorithm is
+    /// not supported
     ///
     /// `algorithm`: If this is None, a random algorithm will be used
-    pub fn new_alice(algorithm: Option<u8>) -> Self {
+    pub fn new_alice(algorithm: Option<u8>) -> Result<Self, EzError> {
+        let algorithm = algorithm.unwrap_or_else(|| {
+            default_rng().gen_range(0, ALGORITHM_COUNT)
+        });
+
+        let data = Self::get_new_alice(algorithm)?;
+        Ok(Self { algorithm, data })
+    }
+
+    /// Creates a new [PostQuantumContainer] for Alice, drawing all randomness (including the algorithm,
+    /// if `algorithm` is None) from `rng` rather than the operating system. Returns an error if the
+    /// algorithm is not supported
+    #[cfg(feature = "std")]
+    pub fn new_alice_with_rng<R: RngCore + CryptoRng>(algorithm: Option<u8>, rng: &mut R) -> Result<Self, EzError> {
         let algorithm = algorithm.unwrap_or_else(|| {
-            rand::thread_rng().gen_range(0, ALGORITHM_COUNT)
+            rng.gen_range(0, ALGORITHM_COUNT)
         });
 
-        let data = Self::get_new_alice(algorithm);
-        let aes_gcm_key = None;
-        Self { algorithm, data, aes_gcm_key, anti_replay_attack: AntiReplayAttackContainerOrdered::default(), node: PQNode::Alice }
+        check_algorithm(algorithm)?;
+        let data = crate::function_pointers::ALICE_WITH_RNG_FP[0](rng);
+        Ok(Self { algorithm, data })
+    }
+
+    /// Creates a new [PostQuantumContainer] for Alice whose keypair is derived deterministically from `seed`,
+    /// e.g. for reproducible tests or keys derived from a master secret. The seed must be kept as secret as the key
+    #[cfg(feature = "std")]
+    pub fn new_alice_from_seed(algorithm: u8, seed: &[u8; SEED_LENGTH_BYTES]) -> Result<Self, EzError> {
+        check_algorithm(algorithm)?;
+        let data = crate::function_pointers::ALICE_FROM_SEED_FP[0](seed);
+        Ok(Self { algorithm, data })
     }
 
-    /// Creates a new [PostQuantumContainer] for Bob. This will panic if the algorithm is
-    /// invalid
-    pub fn new_bob(algorithm: u8, public_key: &[u8]) -> Result<Self, Error> {
+    /// Creates a new [PostQuantumContainer] for Bob. Returns an error if the algorithm is
+    /// not supported or the public key is malformed
+    pub fn new_bob(algorithm: u8, public_key: &[u8]) -> Result<Self, EzError> {
         let data = Self::get_new_bob(algorithm, public_key)?;
-        // We must call the below to refresh the internal state to allow get_shared_secret to function
-        let ss = data.get_shared_secret().unwrap();
-        let key = GenericArray::<u8, _>::from_exact_iter(ss.into_iter().cloned()).ok_or(Error::BadLength {
-            name: "",
-            actual: 0,
-            expected: 0
-        })?;
+        Ok(Self { algorithm, data })
+    }
+
+    /// Creates a new [PostQuantumContainer] for Alice, using an algorithm drawn uniformly from those `policy` permits.
+    /// Returns an error if the policy permits none, or if the generated public key exceeds its size limit
+    pub fn new_alice_with_policy(policy: &Policy) -> Result<Self, EzError> {
+        let permitted: Vec<u8> = policy.permitted().iter().collect();
+        if permitted.is_empty() {
+            return Err(EzError::InvalidInput("the policy permits no algorithm"));
+        }
 
-        let aes_gcm_key = Some(AeadKey::new(&key));
+        let algorithm = permitted[default_rng().gen_range(0, permitted.len())];
+        let container = Self::new_alice(Some(algorithm))?;
+        policy.check(algorithm, container.get_public_key())?;
+        Ok(container)
+    }
 
-        Ok(Self { algorithm, aes_gcm_key, data, anti_replay_attack: AntiReplayAttackContainerOrdered::default(), node: PQNode::Bob })
+    /// Creates a new [PostQuantumContainer] for Bob, returning [EzError::PolicyViolation] if `policy` does not
+    /// permit the algorithm Alice chose or her public key is larger than it allows
+    pub fn new_bob_with_policy(policy: &Policy, algorithm: u8, public_key: &[u8]) -> Result<Self, EzError> {
+        policy.check(algorithm, public_key)?;
+        Self::new_bob(algorithm, public_key)
     }
 
-    /// This should always be called after deserialization
-    fn load_aes_gcm_key(&mut self) {
-        self.aes_gcm_key = Some(AeadKey::new(&GenericArray::clone_from_slice(self.get_shared_secret().unwrap())))
+    /// Creates a new [PostQuantumContainer] for Alice using the strongest algorithm common to Bob's `offer` and the
+    /// algorithms `policy` permits and this build implements. Alice sends the chosen algorithm, the returned [Offer]
+    /// and her public key to Bob, who checks them with [PostQuantumContainer::new_bob_negotiated]. Both offers and the
+    /// choice are bound into the derived keys, so if either offer was tampered with, key confirmation and decryption fail
+    pub fn new_alice_negotiated(offer: &Offer, policy: &Policy) -> Result<(Self, Offer), EzError> {
+        let alice_offer = Offer::from_policy(policy)?;
+        let algorithm = negotiation::select(&alice_offer, offer)?;
+
+        let mut container = Self::new_alice(Some(algorithm))?;
+        container.data.bind_negotiation(negotiation::transcript_hash(offer, &alice_offer, algorithm))?;
+        Ok((container, alice_offer))
+    }
+
+    /// Creates a new [PostQuantumContainer] for Bob after a negotiation. `offer` is the [Offer] Bob sent, and
+    /// `alice_offer`, `algorithm` and `public_key` are Alice's reply. Returns an error if `policy` rejects the algorithm or
+    /// public key, as [PostQuantumContainer::new_bob_with_policy] does, or if `algorithm` is not the algorithm the two
+    /// offers dictate, which means Bob's offer was altered on its way to Alice
+    pub fn new_bob_negotiated(offer: &Offer, alice_offer: &Offer, policy: &Policy, algorithm: u8, public_key: &[u8]) -> Result<Self, EzError> {
+        policy.check(algorithm, public_key)?;
+        if negotiation::select(alice_offer, offer)? != algorithm {
+            return Err(EzError::NegotiationFailed("the chosen algorithm is not the strongest one both sides offered"));
+        }
+
+        let mut container = Self::new_bob(algorithm, public_key)?;
+        container.data.bind_negotiation(negotiation::transcript_hash(offer, alice_offer, algorithm))?;
+        Ok(container)
     }
 
     /// Internally creates shared key after bob sends a response back to Alice
-    pub fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), Error> {
-        //debug_assert_eq!(self.node, PQNode::Alice);
-        self.data.alice_on_receive_ciphertext(ciphertext)?;
-        let ss = self.data.get_shared_secret().unwrap();
-        self.aes_gcm_key = Some(AeadKey::new(&GenericArray::clone_from_slice(ss)));
-        Ok(())
+    pub fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), EzError> {
+        self.data.alice_on_receive_ciphertext(ciphertext)
     }
     /// Gets the public key
     pub fn get_public_key(&self) -> &[u8] {
         self.data.get_public_key()
     }
-    /// Gets the secret key (If node is Alice type)
-    pub fn get_secret_key(&self) -> Result<&[u8], Error> {
+    /// Gets the secret key (If node is Alice type). Exposed through [hazmat::SecretAccess]
+    pub(crate) fn get_secret_key(&self) -> Result<&[u8], EzError> {
         self.data.get_secret_key()
     }
     /// Gets the ciphertext
-    pub fn get_ciphertext(&self) -> Result<&[u8], Error> {
+    pub fn get_ciphertext(&self) -> Result<&[u8], EzError> {
         self.data.get_ciphertext()
     }
-    /// Gets the shared secret
-    pub fn get_shared_secret(&self) -> Result<&[u8], Error> {
+    /// Gets the shared secret. Exposed through [hazmat::SecretAccess]
+    pub(crate) fn get_shared_secret(&self) -> Result<&[u8], EzError> {
         self.data.get_shared_secret()
     }
 
-    /// Serializes the entire package to a vector
-    pub fn serialize_to_vector(&self) -> Result<Vec<u8>, Error> {
-        let export = PostQuantumExport::from(self);
-        Ok(export.serialize_bin())
+    /// Gets the AEAD keyed with the shared secret, once it is known
+    #[cfg(feature = "std")]
+    pub(crate) fn get_aead(&self) -> Option<&AeadKey> {
+        self.data.get_aead()
+    }
+
+    /// Returns the SHA-256 hash of the public key, suitable for identifying or pinning a peer's key
+    pub fn public_key_fingerprint(&self) -> [u8; 32] {
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(&Sha256::digest(self.get_public_key()));
+        fingerprint
+    }
+
+    /// Derives a value from the shared secret and the exchanged public key and ciphertext. Both sides
+    /// compute the same value, so exchanging it confirms that both hold the same shared secret without revealing it
+    pub fn key_confirmation(&self) -> Result<[u8; 32], EzError> {
+        self.data.key_confirmation(self.algorithm)
+    }
+
+    /// Verifies, in constant time, a key confirmation value received from the other side
+    pub fn verify_key_confirmation(&self, other: &[u8]) -> bool {
+        self.key_confirmation().map(|confirmation| bool::from(confirmation.ct_eq(other))).unwrap_or(false)
+    }
+
+    /// Derives `len` bytes of keying material from the shared secret using HKDF-SHA256. Different `label`s and
+    /// `context`s yield independent keys, none of which reveal the shared secret or the AEAD key
+    pub fn export_keying_material(&self, label: &[u8], context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        self.data.export_keying_material(label, context, len)
+    }
+
+    /// Compares the shared secret against `other` in constant time. Returns false if the shared secret is not loaded
+    pub fn shared_secret_eq(&self, other: &[u8]) -> bool {
+        self.get_shared_secret().map(|shared_secret| bool::from(shared_secret.ct_eq(other))).unwrap_or(false)
+    }
+
+    /// Serializes the entire package to a vector. The output holds the secrets in cleartext, so it is zeroed when dropped
+    pub fn serialize_to_vector(&self) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        Ok(PostQuantumExport::from(self).encode())
     }
 
     /// Attempts to deserialize the input bytesm presumed to be of type [PostQuantumExport],
-    /// into a [PostQuantumContainer]
+    /// into a [PostQuantumContainer]. Containers serialized before the format was versioned are also accepted
     pub fn deserialize_from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, EzError> {
-        //let export = bincode2::deserialize::<PostQuantumExport>(bytes.as_ref())?;
-        let export = PostQuantumExport::deserialize_bin(bytes.as_ref()).map_err(|_err| EzError::Generic("Deserialization failure"))?;
-        PostQuantumContainer::try_from(export).map_err(|_err| EzError::Generic("Deserialization failure"))
+        let export = PostQuantumExport::decode(bytes.as_ref())?;
+        PostQuantumContainer::try_from(export)
+    }
+
+    /// Serializes the container and encrypts it under a key derived from `password` with Argon2id, using
+    /// the default [password::PasswordParams]. Use this instead of [PostQuantumContainer::serialize_to_vector]
+    /// when the output is persisted, since the latter holds the secrets in cleartext
+    pub fn serialize_encrypted<P: AsRef<[u8]>>(&self, password: P) -> Result<Vec<u8>, EzError> {
+        self.serialize_encrypted_with_params(password, &password::PasswordParams::default())
+    }
+
+    /// Like [PostQuantumContainer::serialize_encrypted], with custom Argon2id parameters
+    pub fn serialize_encrypted_with_params<P: AsRef<[u8]>>(&self, password: P, params: &password::PasswordParams) -> Result<Vec<u8>, EzError> {
+        let export = PostQuantumExport::from(self).encode();
+        password::encrypt(&export, password.as_ref(), params)
+    }
+
+    /// Decrypts and deserializes the output of [PostQuantumContainer::serialize_encrypted]. The Argon2id parameters
+    /// and salt are read from the input
+    pub fn deserialize_encrypted<B: AsRef<[u8]>, P: AsRef<[u8]>>(bytes: B, password: P) -> Result<Self, EzError> {
+        let export = password::decrypt(bytes.as_ref(), password.as_ref())?;
+        Self::deserialize_from_bytes(export.as_slice())
     }
 
     /// Returns either Alice or Bob
     pub fn get_node_type(&self) -> PQNode {
-        self.node
+        self.data.get_node_type()
     }
 
     /// Returns the byte-sized representation of the algorithm used
@@ -146,157 +297,225 @@ impl PostQuantumContainer {
 
     /// Encrypts the data. This will return an error if the internal shared secret is not set
     pub fn encrypt<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<Vec<u8>, EzError> where Self: Sized {
-        let input = input.as_ref();
-        let nonce = nonce.as_ref();
-        let nonce = GenericArray::from_slice(nonce);
-
-        // if the shared secret is loaded, the AES GCM abstraction should too.
-
-        if let Some(aes_gcm_key) = self.aes_gcm_key.as_ref() {
-            match aes_gcm_key.encrypt(nonce, input) {
-                Err(_) => {
-                    Err(EzError::AesGcmEncryptionFailure)
-                },
-
-                Ok(vec) => {
-                    Ok(vec)
-                }
-            }
-        } else {
-            Err(EzError::SharedSecretNotLoaded)
-        }
+        self.data.encrypt(input.as_ref(), nonce.as_ref())
     }
 
     pub fn protect_packet_in_place<R: AsRef<[u8]>>(&self, header_len: usize, full_packet: &mut BytesMut, nonce: R) -> Result<(), EzError> {
-        let nonce = nonce.as_ref();
-        let nonce = GenericArray::from_slice(nonce);
-        let mut payload = full_packet.split_off(header_len);
-        let header = full_packet;
-
-        // next, push the ARA-generated PID
-        payload.put_u64(self.anti_replay_attack.get_next_pid());
-        let payload_len = payload.len();
-
-        let mut in_place_payload = InPlaceBytesMut::new(&mut payload, 0..payload_len).ok_or_else(|| EzError::Generic("Bad window range"))?;
-        if let Some(aes_gcm_key) = self.aes_gcm_key.as_ref() {
-            aes_gcm_key.encrypt_in_place(nonce, &header[0..header_len], &mut in_place_payload).map_err(|_| EzError::AesGcmEncryptionFailure)?;
-            header.unsplit(payload);
-            Ok(())
-        } else {
-            Err(EzError::SharedSecretNotLoaded)
-        }
+        self.data.protect_packet_in_place(header_len, full_packet, nonce.as_ref())
     }
 
     /// Validates the AAD (header) and produces the plaintext given the input of ciphertext
-    pub fn validate_packet_in_place<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, mut payload: &mut BytesMut, nonce: R) -> Result<(), EzError> {
-        let nonce = nonce.as_ref();
-        let nonce = GenericArray::from_slice(nonce);
-        let header = header.as_ref();
-        let payload_len = payload.len();
-
-        let mut in_place_payload = InPlaceBytesMut::new(&mut payload, 0..payload_len).ok_or_else(|| EzError::Generic("Bad window range"))?;
-        if let Some(aes_gcm_key) = self.aes_gcm_key.as_ref() {
-            aes_gcm_key.decrypt_in_place(nonce, header, &mut in_place_payload).map_err(|_| EzError::AesGcmDecryptionFailure)
-                .and_then(|_| {
-                    // get the last 8 bytes of the payload
-                    let end_idx = payload.len();
-                    let start_idx = end_idx.saturating_sub(8);
-                    if end_idx - start_idx == 8 {
-                        let mut array: [u8; 8] = Default::default();
-                        array.copy_from_slice(&payload[start_idx..end_idx]);
-
-                        if self.anti_replay_attack.on_pid_received(u64::from_be_bytes(array)) {
-                            // remove the PID from the payload
-                            payload.truncate(start_idx);
-                            return Ok(())
-                        }
-                    }
-
-                    Err(EzError::Generic("Anti-replay-attack: invalid"))
-                })
-        } else {
-            Err(EzError::SharedSecretNotLoaded)
-        }
+    pub fn validate_packet_in_place<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut BytesMut, nonce: R) -> Result<(), EzError> {
+        self.data.validate_packet_in_place(header.as_ref(), payload, nonce.as_ref())
+    }
+
+    /// An allocation-free version of [PostQuantumContainer::protect_packet_in_place]. The first `packet_len` bytes of `buffer`
+    /// hold the header and payload, and the buffer must have at least [PACKET_OVERHEAD_BYTES] of tail room for the PID and tag.
+    /// Returns the length of the protected packet, or an error if the tail room is too short
+    pub fn protect_packet_in_slice<R: AsRef<[u8]>>(&self, header_len: usize, buffer: &mut [u8], packet_len: usize, nonce: R) -> Result<usize, EzError> {
+        self.data.protect_packet_in_slice(header_len, buffer, packet_len, nonce.as_ref())
+    }
+
+    /// An allocation-free version of [PostQuantumContainer::validate_packet_in_place]. The plaintext is left at the start of
+    /// `payload`, and its length is returned
+    pub fn validate_packet_in_slice<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut [u8], nonce: R) -> Result<usize, EzError> {
+        self.data.validate_packet_in_slice(header.as_ref(), payload, nonce.as_ref())
+    }
+
+    /// Like [PostQuantumContainer::encrypt], but returns the authentication tag separately from the ciphertext
+    pub fn encrypt_detached<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<(Vec<u8>, Tag), EzError> {
+        let mut buffer = input.as_ref().to_vec();
+        let tag = self.encrypt_in_place_detached(&mut buffer, &[], nonce)?;
+        Ok((buffer, tag))
+    }
+
+    /// Decrypts a ciphertext produced by [PostQuantumContainer::encrypt_detached]
+    pub fn decrypt_detached<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, tag: &Tag, nonce: R) -> Result<Vec<u8>, EzError> {
+        let mut buffer = input.as_ref().to_vec();
+        self.decrypt_in_place_detached(&mut buffer, &[], tag, nonce)?;
+        Ok(buffer)
+    }
+
+    /// Encrypts `buffer` in place, authenticating `aad` alongside it, and returns the tag
+    pub fn encrypt_in_place_detached<R: AsRef<[u8]>>(&self, buffer: &mut [u8], aad: &[u8], nonce: R) -> Result<Tag, EzError> {
+        self.data.encrypt_in_place_detached(buffer, aad, nonce.as_ref())
+    }
+
+    /// Decrypts `buffer` in place if `tag` authenticates it and `aad`
+    pub fn decrypt_in_place_detached<R: AsRef<[u8]>>(&self, buffer: &mut [u8], aad: &[u8], tag: &Tag, nonce: R) -> Result<(), EzError> {
+        self.data.decrypt_in_place_detached(buffer, aad, tag, nonce.as_ref())
+    }
+
+    /// Like [PostQuantumContainer::protect_packet_in_place], but the tag is returned instead of appended. The PID is still
+    /// appended to the payload
+    pub fn protect_packet_in_place_detached<R: AsRef<[u8]>>(&self, header_len: usize, full_packet: &mut BytesMut, nonce: R) -> Result<Tag, EzError> {
+        self.data.protect_packet_in_place_detached(header_len, full_packet, nonce.as_ref())
+    }
+
+    /// Validates a packet protected by [PostQuantumContainer::protect_packet_in_place_detached]. On success, the PID is
+    /// removed from the payload, leaving the plaintext
+    pub fn validate_packet_in_place_detached<H: AsRef<[u8]>, R: AsRef<[u8]>>(&self, header: H, payload: &mut BytesMut, tag: &Tag, nonce: R) -> Result<(), EzError> {
+        self.data.validate_packet_in_place_detached(header.as_ref(), payload, tag, nonce.as_ref())
+    }
+
+    /// Like [PostQuantumContainer::protect_packet_in_place_detached], but the header may be scattered across several
+    /// buffers and the payload is encrypted where it lies, without being moved or grown. Returns the tag and the PID,
+    /// both of which must be sent with the packet. The PID is authenticated along with the header
+    ///
+    /// The payload must be a single buffer: the AEADs cannot encrypt a message split across buffers without first
+    /// copying it into one
+    #[cfg(feature = "std")]
+    pub fn protect_packet_vectored<R: AsRef<[u8]>>(&self, header: &[IoSlice], payload: &mut [u8], nonce: R) -> Result<(Tag, u64), EzError> {
+        self.data.protect_packet_vectored(header, payload, nonce.as_ref())
+    }
+
+    /// Validates a packet protected by [PostQuantumContainer::protect_packet_vectored], using the `tag` and `pid` sent
+    /// with it, and decrypts the payload in place. On failure, the payload is zeroed
+    #[cfg(feature = "std")]
+    pub fn validate_packet_vectored<R: AsRef<[u8]>>(&self, header: &[IoSlice], payload: &mut [u8], tag: &Tag, pid: u64, nonce: R) -> Result<(), EzError> {
+        self.data.validate_packet_vectored(header, payload, tag, pid, nonce.as_ref())
     }
 
     /// Encrypts the data. This will return an error if the internal shared secret is not set
     pub fn decrypt<T: AsRef<[u8]>, R: AsRef<[u8]>>(&self, input: T, nonce: R) -> Result<Vec<u8>, EzError> where Self: Sized {
-        let input = input.as_ref();
-        let nonce = nonce.as_ref();
-
-        let nonce = GenericArray::from_slice(nonce);
-        // if the shared secret is loaded, the AES GCM abstraction should too.
-
-        if let Some(aes_gcm_key) = self.aes_gcm_key.as_ref() {
-            match aes_gcm_key.decrypt(nonce, input) {
-                Err(_) => {
-                    Err(EzError::AesGcmDecryptionFailure)
-                },
-
-                Ok(vec) => {
-                    Ok(vec)
-                }
-            }
-        } else {
-            Err(EzError::SharedSecretNotLoaded)
-        }
+        self.data.decrypt(input.as_ref(), nonce.as_ref())
     }
 
     /// This, for now, only gets FIRESABER
-    fn get_new_alice(algorithm: u8) -> Box<dyn PostQuantumType> {
-        assert!(algorithm < ALGORITHM_COUNT);
-        crate::function_pointers::ALICE_FP[0]()
+    fn get_new_alice(algorithm: u8) -> Result<Box<dyn PostQuantumType>, EzError> {
+        check_algorithm(algorithm)?;
+        Ok(crate::function_pointers::ALICE_FP[0]())
     }
 
     /// This, for now, only gets FIRESABER
-    fn get_new_bob(algorithm: u8, public_key: &[u8]) -> Result<Box<dyn PostQuantumType>, Error> {
-        assert!(algorithm < ALGORITHM_COUNT);
+    fn get_new_bob(algorithm: u8, public_key: &[u8]) -> Result<Box<dyn PostQuantumType>, EzError> {
+        check_algorithm(algorithm)?;
         crate::function_pointers::BOB_FP[0](public_key)
     }
+
+    /// Recovers the shared secret from a ciphertext using only Alice's secret key.
+    /// This, for now, only gets FIRESABER
+    pub(crate) fn get_decapsulation(algorithm: u8, secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, EzError> {
+        check_algorithm(algorithm)?;
+        crate::function_pointers::DECAPSULATE_FP[0](secret_key, ciphertext)
+    }
+}
+
+impl<K: container::Algorithm> From<container::Container<K>> for PostQuantumContainer {
+    fn from(container: container::Container<K>) -> Self {
+        Self { algorithm: K::ALGORITHM, data: Box::new(container) }
+    }
 }
 
 impl Clone for PostQuantumContainer {
     fn clone(&self) -> Self {
-        let ser = self.serialize_to_vector().unwrap();
-        PostQuantumContainer::deserialize_from_bytes(ser).unwrap()
+        let ser = self.serialize_to_vector().expect("serializing a container cannot fail");
+        PostQuantumContainer::deserialize_from_bytes(ser.as_slice()).expect("a container always round-trips through its own export")
     }
 }
 
-impl TryFrom<PostQuantumExport> for PostQuantumContainer {
-    type Error = Error;
+impl core::fmt::Debug for PostQuantumContainer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PostQuantumContainer")
+            .field("algorithm", &self.algorithm)
+            .field("node", &self.get_node_type())
+            .field("public_key_fingerprint", &hex_string(&self.public_key_fingerprint()))
+            .field("secret_key", &self.get_secret_key().ok().map(|_| "<redacted>"))
+            .field("shared_secret", &self.get_shared_secret().ok().map(|_| "<redacted>"))
+            .finish()
+    }
+}
 
-    fn try_from(export: PostQuantumExport) -> Result<Self, Self::Error> {
-        // First, create the type, pretending this node is Bob since we already
-        // have the public key
-        let algorithm = export.algorithm;
-        let node = if export.node == 0 {
-            PQNode::Alice
-        } else {
-            PQNode::Bob
-        };
-
-        let mut container = PostQuantumContainer::new_bob(algorithm, export.public_key.as_slice())?;
-        container.node = node;
-        container.data.set_public_key(export.public_key.as_slice()).unwrap();
-
-        // Now, begin setting the values
-        if let Some(secret_key) = export.secret_key {
-            container.data.set_secret_key(secret_key.as_slice())?;
-        }
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
 
-        if let Some(ciphertext) = export.ciphertext {
-            container.data.set_ciphertext(ciphertext.as_slice())?;
-        }
+/// The thread-local generator with std, and the operating system's generator without it
+#[cfg(feature = "std")]
+pub(crate) fn default_rng() -> rand::rngs::ThreadRng {
+    rand::thread_rng()
+}
 
-        if let Some(shared_secret) = export.shared_secret {
-            let shared_secret_slice = shared_secret.as_slice();
-            container.data.set_shared_secret(shared_secret_slice)?;
+/// The thread-local generator with std, and [getrandom] without it
+#[cfg(not(feature = "std"))]
+pub(crate) fn default_rng() -> GetrandomRng {
+    GetrandomRng
+}
 
-        }
+/// Draws directly from [getrandom], which accepts a custom source on targets without an operating system
+#[cfg(not(feature = "std"))]
+pub(crate) struct GetrandomRng;
 
-        container.load_aes_gcm_key();
+#[cfg(not(feature = "std"))]
+impl RngCore for GetrandomRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
 
-        Ok(container)
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("the randomness source failed")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        getrandom::getrandom(dest).map_err(|err| rand::Error::from(err.code()))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl CryptoRng for GetrandomRng {}
+
+pub(crate) fn check_algorithm(algorithm: u8) -> Result<(), EzError> {
+    if algorithm < ALGORITHM_COUNT {
+        Ok(())
+    } else {
+        Err(EzError::UnsupportedAlgorithm(algorithm))
+    }
+}
+
+/// Returns [EzError::UnsupportedAlgorithm] if this build has no implementation of `algorithm`
+pub(crate) fn check_backend(algorithm: u8) -> Result<(), EzError> {
+    if algorithm_dictionary::has_backend(algorithm) {
+        Ok(())
+    } else {
+        Err(EzError::UnsupportedAlgorithm(algorithm))
+    }
+}
+
+/// Interprets `nonce` as an AEAD nonce, returning an error instead of panicking if the length is wrong
+fn nonce_from_slice<N: ArrayLength<u8>>(nonce: &[u8]) -> Result<&GenericArray<u8, N>, EzError> {
+    if nonce.len() == N::USIZE {
+        Ok(GenericArray::from_slice(nonce))
+    } else {
+        Err(EzError::InvalidLength { what: "nonce", expected: N::USIZE, actual: nonce.len() })
+    }
+}
+
+/// Wipes the expanded key held by the AEAD. aes-gcm-siv does not do this on its own
+pub(crate) fn wipe_aead_key(key: &mut AeadKey) {
+    // SAFETY: the AEAD key types are flat arrays of integers (no pointers or owned heap data), and all-zero is a valid bit pattern
+    unsafe { zeroize::zeroize_flat_type(key as *mut AeadKey) }
+}
+
+impl TryFrom<PostQuantumExport> for PostQuantumContainer {
+    type Error = EzError;
+
+    fn try_from(export: PostQuantumExport) -> Result<Self, Self::Error> {
+        let algorithm = export.algorithm;
+        if export.aead_suite != crate::export::AEAD_SUITE {
+            return Err(EzError::UnsupportedAeadSuite(export.aead_suite));
+        }
+
+        check_algorithm(algorithm)?;
+        let data = crate::function_pointers::IMPORT_FP[0](&export)?;
+        Ok(Self { algorithm, data })
     }
 }
 
@@ -358,161 +577,114 @@ pub mod algorithm_dictionary {
     pub const PAPABEAREPHEM: u8 = 40;
 
     pub const SABER: u8 = 41;
+
+    /// The NIST security category (1 to 5) claimed for `algorithm`, or None if the byte names no algorithm
+    pub const fn nist_level(algorithm: u8) -> Option<u8> {
+        match algorithm {
+            FRODOKEM640AES | FRODOKEM640SHAKE | KYBER512 | KYBER51290S | LEDAKEMLT12 | LIGHTSABER | MCELIECE348864
+            | MCELIECE348864F | NEWHOPE512CCA | NEWHOPE512CPA | NTRUHPS2048509 => Some(1),
+            BABYBEAR | BABYBEAREPHEM => Some(2),
+            FRODOKEM976AES | FRODOKEM976SHAKE | KYBER768 | KYBER76890S | LEDAKEMLT32 | MCELIECE460896 | MCELIECE460896F
+            | NTRUHPS2048677 | NTRUHRSS701 | SABER => Some(3),
+            MAMABEAR | MAMABEAREPHEM => Some(4),
+            FIRESABER | FRODOKEM1344AES | FRODOKEM1344SHAKE | KYBER1024 | KYBER102490S | LEDAKEMLT52 | MCELIECE6688128
+            | MCELIECE6688128F | MCELIECE6960119 | MCELIECE6960119F | MCELIECE8192128 | MCELIECE8192128F | NEWHOPE1024CCA
+            | NEWHOPE1024CPA | NTRUHPS4096821 | PAPABEAR | PAPABEAREPHEM => Some(5),
+            _ => None
+        }
+    }
+
+    /// The length of `algorithm`'s public keys, as specified by the scheme, or None if the byte names no algorithm
+    pub const fn public_key_bytes(algorithm: u8) -> Option<usize> {
+        match algorithm {
+            BABYBEAR | BABYBEAREPHEM => Some(804),
+            FIRESABER => Some(1312),
+            FRODOKEM640AES | FRODOKEM640SHAKE => Some(9616),
+            FRODOKEM976AES | FRODOKEM976SHAKE => Some(15632),
+            FRODOKEM1344AES | FRODOKEM1344SHAKE => Some(21520),
+            KYBER512 | KYBER51290S => Some(800),
+            KYBER768 | KYBER76890S => Some(1184),
+            KYBER1024 | KYBER102490S => Some(1568),
+            LEDAKEMLT12 => Some(6520),
+            LEDAKEMLT32 => Some(12032),
+            LEDAKEMLT52 => Some(19040),
+            LIGHTSABER => Some(672),
+            MAMABEAR | MAMABEAREPHEM => Some(1194),
+            MCELIECE348864 | MCELIECE348864F => Some(261120),
+            MCELIECE460896 | MCELIECE460896F => Some(524160),
+            MCELIECE6688128 | MCELIECE6688128F => Some(1044992),
+            MCELIECE6960119 | MCELIECE6960119F => Some(1047319),
+            MCELIECE8192128 | MCELIECE8192128F => Some(1357824),
+            NEWHOPE512CCA | NEWHOPE512CPA => Some(928),
+            NEWHOPE1024CCA | NEWHOPE1024CPA => Some(1824),
+            NTRUHPS2048509 => Some(699),
+            NTRUHPS2048677 => Some(930),
+            NTRUHPS4096821 => Some(1230),
+            NTRUHRSS701 => Some(1138),
+            PAPABEAR | PAPABEAREPHEM => Some(1584),
+            SABER => Some(992),
+            _ => None
+        }
+    }
+
+    /// Returns true if `algorithm` is IND-CCA secure. The ephemeral ThreeBears and CPA NewHope variants are only IND-CPA secure
+    pub const fn is_ind_cca(algorithm: u8) -> bool {
+        !matches!(algorithm, BABYBEAREPHEM | MAMABEAREPHEM | PAPABEAREPHEM | NEWHOPE512CPA | NEWHOPE1024CPA) && algorithm < ALGORITHM_COUNT
+    }
+
+    /// Returns true if this build has an implementation of `algorithm`. Only [FIRESABER] is currently backed
+    pub const fn has_backend(algorithm: u8) -> bool {
+        algorithm == FIRESABER
+    }
 }
 
-/// Used to get different algorithm types dynamically
-pub trait PostQuantumType: Send + Sync {
-    /// Creates a new self for the initiating node
-    fn new_alice() -> Self where Self: Sized;
-    /// Creates a new self for the receiving node
-    fn new_bob(public_key: &[u8]) -> Result<Self, Error> where Self: Sized;
+/// Used to get different algorithm types dynamically. Implemented by every [container::Container] using the crate's AEAD
+pub(crate) trait PostQuantumType: Send + Sync {
     /// Internally creates shared key after bob sends a response back to Alice
-    fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), Error>;
+    fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), EzError>;
     /// Gets the public key
     fn get_public_key(&self) -> &[u8];
     /// Gets the secret key (If node is Alice type)
-    fn get_secret_key(&self) -> Result<&[u8], Error>;
+    fn get_secret_key(&self) -> Result<&[u8], EzError>;
     /// Gets the ciphertext
-    fn get_ciphertext(&self) -> Result<&[u8], Error>;
+    fn get_ciphertext(&self) -> Result<&[u8], EzError>;
     /// Gets the shared secret
-    fn get_shared_secret(&self) -> Result<&[u8], Error>;
-    /// Sets the secret key
-    fn set_secret_key(&mut self, secret_key: &[u8]) -> Result<(), Error>;
-    /// Sets the ciphertext
-    fn set_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), Error>;
-    /// Sets the shared key
-    fn set_shared_secret(&mut self, shared_key: &[u8]) -> Result<(), Error>;
-    /// Sets the public key
-    fn set_public_key(&mut self, public_key: &[u8]) -> Result<(), Error>;
-}
-
-macro_rules! create_struct {
-    ($base:ident, $name:ident) => {
-        /// Auto generated
-        #[derive(Clone)]
-        pub(crate) struct $base {
-            /// The public key. Both Alice and Bob get this
-            public_key: pqcrypto::kem::$name::PublicKey,
-            /// Only Alice gets this one
-            secret_key: Option<pqcrypto::kem::$name::SecretKey>,
-            /// Both Bob and Alice get this one
-            ciphertext: Option<pqcrypto::kem::$name::Ciphertext>,
-            /// Both Alice and Bob get this (at the end)
-            shared_secret: Option<pqcrypto::kem::$name::SharedSecret>
-        }
-
-        //unsafe impl Send for $base {}
-        //unsafe impl Sync for $base {}
-
-        impl PostQuantumType for $base {
-
-            fn new_alice() -> Self {
-                let (public_key, secret_key) = pqcrypto::kem::$name::keypair();
-                let ciphertext = None;
-                let shared_secret = None;
-                let secret_key = Some(secret_key);
-
-                Self { public_key, secret_key, ciphertext, shared_secret }
-            }
-
-            fn new_bob(public_key: &[u8]) -> Result<Self, Error> {
-                let public_key = pqcrypto::kem::$name::PublicKey::from_bytes(public_key)?;
-                let (shared_secret, ciphertext) = pqcrypto::kem::$name::encapsulate(&public_key);
-                let secret_key = None;
-                let shared_secret = Some(shared_secret);
-                let ciphertext = Some(ciphertext);
-
-                Ok(Self { public_key, secret_key, ciphertext, shared_secret })
-            }
-
-            fn alice_on_receive_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), Error> {
-                // These functions should only be called once upon response back from Bob
-                assert!(self.shared_secret.is_none());
-                assert!(self.ciphertext.is_none());
-                assert!(self.secret_key.is_some());
-
-                let ciphertext = pqcrypto::kem::$name::Ciphertext::from_bytes(ciphertext)?;
-
-                if let Some(secret_key) = self.secret_key.as_ref() {
-                    let shared_secret = pqcrypto::kem::$name::decapsulate(&ciphertext, secret_key);
-                    self.ciphertext = Some(ciphertext);
-                    self.shared_secret = Some(shared_secret);
-                    Ok(())
-                } else {
-                    Err(Error::BadLength {
-                        name: "Unable to get secret key",
-                        actual: 0,
-                        expected: 0
-                    })
-                }
-            }
-
-            fn get_public_key(&self) -> &[u8] {
-                self.public_key.as_bytes()
-            }
-
-            fn get_secret_key(&self) -> Result<&[u8], Error> {
-                if let Some(secret_key) = self.secret_key.as_ref() {
-                    Ok(secret_key.as_bytes())
-                } else {
-                    Err(get_generic_error("Unable to get secret key"))
-                }
-            }
-
-            fn get_ciphertext(&self) -> Result<&[u8], Error> {
-                if let Some(ciphertext) = self.ciphertext.as_ref() {
-                    Ok(ciphertext.as_bytes())
-                } else {
-                    Err(get_generic_error("Unable to get ciphertext"))
-                }
-            }
-
-            fn get_shared_secret(&self) -> Result<&[u8], Error> {
-                if let Some(shared_secret) = self.shared_secret.as_ref() {
-                    Ok(shared_secret.as_bytes())
-                } else {
-                    Err(get_generic_error("Unable to get secret key"))
-                }
-            }
-
-            fn set_public_key(&mut self, public_key: &[u8]) -> Result<(), Error> {
-                let public_key = pqcrypto::kem::$name::PublicKey::from_bytes(public_key)?;
-                self.public_key = public_key;
-
-                Ok(())
-            }
-
-            /// Sets the secret key
-            fn set_secret_key(&mut self, secret_key: &[u8]) -> Result<(), Error> {
-                let secret_key = pqcrypto::kem::$name::SecretKey::from_bytes(secret_key)?;
-                self.secret_key = Some(secret_key);
-
-                Ok(())
-            }
-
-            /// Sets the ciphertext
-            fn set_ciphertext(&mut self, ciphertext: &[u8]) -> Result<(), Error> {
-                let ciphertext = pqcrypto::kem::$name::Ciphertext::from_bytes(ciphertext)?;
-                self.ciphertext = Some(ciphertext);
-
-                Ok(())
-            }
-
-            /// Sets the shared key
-            fn set_shared_secret(&mut self, shared_secret: &[u8]) -> Result<(), Error> {
-                let shared_secret = pqcrypto::kem::$name::SharedSecret::from_bytes(shared_secret)?;
-                self.shared_secret = Some(shared_secret);
-
-                Ok(())
-            }
-        }
-    };
+    fn get_shared_secret(&self) -> Result<&[u8], EzError>;
+    /// Returns either Alice or Bob
+    fn get_node_type(&self) -> PQNode;
+    /// Returns the inbound and outbound replay counters
+    fn get_counters(&self) -> (u64, u64);
+    /// Gets the AEAD keyed with the shared secret
+    #[cfg(feature = "std")]
+    fn get_aead(&self) -> Option<&AeadKey>;
+    /// Computes the key confirmation value, binding `algorithm` as the algorithm byte
+    fn key_confirmation(&self, algorithm: u8) -> Result<[u8; 32], EzError>;
+    /// Binds the negotiation transcript into the derived keys
+    fn bind_negotiation(&mut self, transcript: [u8; 32]) -> Result<(), EzError>;
+    /// Gets the negotiation transcript, if the algorithm was negotiated
+    fn get_negotiation(&self) -> Option<&[u8; 32]>;
+    fn export_keying_material(&self, label: &[u8], context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, EzError>;
+    fn encrypt(&self, input: &[u8], nonce: &[u8]) -> Result<Vec<u8>, EzError>;
+    fn decrypt(&self, input: &[u8], nonce: &[u8]) -> Result<Vec<u8>, EzError>;
+    fn protect_packet_in_place(&self, header_len: usize, full_packet: &mut BytesMut, nonce: &[u8]) -> Result<(), EzError>;
+    fn validate_packet_in_place(&self, header: &[u8], payload: &mut BytesMut, nonce: &[u8]) -> Result<(), EzError>;
+    fn protect_packet_in_slice(&self, header_len: usize, buffer: &mut [u8], packet_len: usize, nonce: &[u8]) -> Result<usize, EzError>;
+    fn validate_packet_in_slice(&self, header: &[u8], payload: &mut [u8], nonce: &[u8]) -> Result<usize, EzError>;
+    fn encrypt_in_place_detached(&self, buffer: &mut [u8], aad: &[u8], nonce: &[u8]) -> Result<Tag, EzError>;
+    fn decrypt_in_place_detached(&self, buffer: &mut [u8], aad: &[u8], tag: &Tag, nonce: &[u8]) -> Result<(), EzError>;
+    fn protect_packet_in_place_detached(&self, header_len: usize, full_packet: &mut BytesMut, nonce: &[u8]) -> Result<Tag, EzError>;
+    fn validate_packet_in_place_detached(&self, header: &[u8], payload: &mut BytesMut, tag: &Tag, nonce: &[u8]) -> Result<(), EzError>;
+    #[cfg(feature = "std")]
+    fn protect_packet_vectored(&self, header: &[IoSlice], payload: &mut [u8], nonce: &[u8]) -> Result<(Tag, u64), EzError>;
+    #[cfg(feature = "std")]
+    fn validate_packet_vectored(&self, header: &[IoSlice], payload: &mut [u8], tag: &Tag, pid: u64, nonce: &[u8]) -> Result<(), EzError>;
 }
 
 pub(crate) mod function_pointers {
+    use alloc::boxed::Box;
     use crate::PostQuantumType;
     //use crate::algorithm_dictionary::ALGORITHM_COUNT;
-    use pqcrypto::traits::Error;
+    use crate::ez_error::EzError;
 
     macro_rules! box_alice {
     ($constructor:expr) => {{
@@ -525,10 +697,34 @@ pub(crate) mod function_pointers {
     }};
 }
 
+    #[cfg(feature = "std")]
+    macro_rules! box_alice_with_rng {
+    ($constructor:expr) => {{
+        #[inline(never)]
+        fn alice_with_rng_box_fn(rng: &mut dyn crate::CryptoRngCore) -> Box<dyn PostQuantumType> {
+            Box::new(($constructor)(rng))
+        }
+
+        alice_with_rng_box_fn
+    }};
+}
+
+    #[cfg(feature = "std")]
+    macro_rules! box_alice_from_seed {
+    ($constructor:expr) => {{
+        #[inline(never)]
+        fn alice_from_seed_box_fn(seed: &[u8; crate::SEED_LENGTH_BYTES]) -> Box<dyn PostQuantumType> {
+            Box::new(($constructor)(seed))
+        }
+
+        alice_from_seed_box_fn
+    }};
+}
+
     macro_rules! box_bob {
     ($constructor:expr) => {{
         #[inline(never)]
-        fn bob_box_fn(arr: &[u8]) -> Result<Box<dyn PostQuantumType>, Error> {
+        fn bob_box_fn(arr: &[u8]) -> Result<Box<dyn PostQuantumType>, EzError> {
             Ok(Box::new(($constructor)(arr)?))
         }
 
@@ -536,165 +732,133 @@ pub(crate) mod function_pointers {
     }};
 }
 
+    macro_rules! box_import {
+    ($constructor:expr) => {{
+        #[inline(never)]
+        fn import_box_fn(export: &crate::export::PostQuantumExport) -> Result<Box<dyn PostQuantumType>, EzError> {
+            Ok(Box::new(($constructor)(export)?))
+        }
+
+        import_box_fn
+    }};
+}
+
     pub(crate) static ALICE_FP: [fn() -> Box<dyn PostQuantumType>; 1] = [
-        //box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_babybear::new_alice),
-        //box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_babybearephem::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_firesaber::new_alice),
+        //box_alice!(<crate::container::Container<crate::container::Babybear>>::new_alice),
+        //box_alice!(<crate::container::Container<crate::container::Babybearephem>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Firesaber>>::new_alice),
         /*
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem640aes::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem640shake::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem976aes::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem976shake::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem1344aes::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem1344shake::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber512::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber768::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber1024::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber51290s::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber76890s::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber102490s::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt12::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt32::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt52::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_lightsaber::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mamabear::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mamabearephem::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece348864::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece348864f::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece460896::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece460896f::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6688128::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6688128f::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6960119::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6960119f::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece8192128::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece8192128f::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope512cca::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope512cpa::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope1024cca::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope1024cpa::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps2048509::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps2048677::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps4096821::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhrss701::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_papabear::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_papabearephem::new_alice),
-        box_alice!(crate::post_quantum_structs::PostQuantumAlgorithmData_saber::new_alice)*/
+        box_alice!(<crate::container::Container<crate::container::Frodokem640aes>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Frodokem640shake>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Frodokem976aes>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Frodokem976shake>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Frodokem1344aes>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Frodokem1344shake>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber512>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber768>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber1024>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber51290s>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber76890s>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Kyber102490s>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ledakemlt12>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ledakemlt32>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ledakemlt52>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Lightsaber>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mamabear>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mamabearephem>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece348864>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece348864f>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece460896>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece460896f>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece6688128>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece6688128f>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece6960119>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece6960119f>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece8192128>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Mceliece8192128f>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Newhope512cca>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Newhope512cpa>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Newhope1024cca>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Newhope1024cpa>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ntruhps2048509>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ntruhps2048677>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ntruhps4096821>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Ntruhrss701>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Papabear>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Papabearephem>>::new_alice),
+        box_alice!(<crate::container::Container<crate::container::Saber>>::new_alice)*/
     ];
 
-    pub(crate) static BOB_FP: [fn(&[u8]) -> Result<Box<dyn PostQuantumType>, Error>; 1] = [
+    #[cfg(feature = "std")]
+    type AliceWithRngFn = fn(&mut dyn crate::CryptoRngCore) -> Box<dyn PostQuantumType>;
+    #[cfg(feature = "std")]
+    type AliceFromSeedFn = fn(&[u8; crate::SEED_LENGTH_BYTES]) -> Box<dyn PostQuantumType>;
+    type BobFn = fn(&[u8]) -> Result<Box<dyn PostQuantumType>, EzError>;
+    type DecapsulateFn = fn(&[u8], &[u8]) -> Result<zeroize::Zeroizing<alloc::vec::Vec<u8>>, EzError>;
+    type ImportFn = fn(&crate::export::PostQuantumExport) -> Result<Box<dyn PostQuantumType>, EzError>;
+
+    pub(crate) static BOB_FP: [BobFn; 1] = [
         /*
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_babybear::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_babybearephem::new_bob),*/
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_firesaber::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Babybear>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Babybearephem>>::new_bob),*/
+        box_bob!(<crate::container::Container<crate::container::Firesaber>>::new_bob),
         /*
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem640aes::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem640shake::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem976aes::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem976shake::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem1344aes::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_frodokem1344shake::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber512::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber768::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber1024::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber51290s::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber76890s::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_kyber102490s::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt12::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt32::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ledakemlt52::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_lightsaber::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mamabear::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mamabearephem::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece348864::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece348864f::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece460896::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece460896f::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6688128::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6688128f::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6960119::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece6960119f::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece8192128::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_mceliece8192128f::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope512cca::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope512cpa::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope1024cca::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_newhope1024cpa::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps2048509::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps2048677::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhps4096821::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_ntruhrss701::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_papabear::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_papabearephem::new_bob),
-        box_bob!(crate::post_quantum_structs::PostQuantumAlgorithmData_saber::new_bob)*/
+        box_bob!(<crate::container::Container<crate::container::Frodokem640aes>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Frodokem640shake>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Frodokem976aes>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Frodokem976shake>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Frodokem1344aes>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Frodokem1344shake>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber512>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber768>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber1024>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber51290s>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber76890s>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Kyber102490s>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ledakemlt12>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ledakemlt32>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ledakemlt52>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Lightsaber>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mamabear>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mamabearephem>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece348864>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece348864f>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece460896>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece460896f>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece6688128>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece6688128f>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece6960119>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece6960119f>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece8192128>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Mceliece8192128f>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Newhope512cca>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Newhope512cpa>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Newhope1024cca>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Newhope1024cpa>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ntruhps2048509>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ntruhps2048677>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ntruhps4096821>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Ntruhrss701>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Papabear>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Papabearephem>>::new_bob),
+        box_bob!(<crate::container::Container<crate::container::Saber>>::new_bob)*/
     ];
-}
 
-/// A set of auto generated structs corresponding to one of many possible encryption schemes
-pub(crate) mod post_quantum_structs {
-    use pqcrypto::traits::kem::*;
-    use super::PostQuantumType;
-    use pqcrypto::traits::Error;
+    #[cfg(feature = "std")]
+    pub(crate) static ALICE_WITH_RNG_FP: [AliceWithRngFn; 1] = [
+        box_alice_with_rng!(<crate::container::Container<crate::container::Firesaber>>::new_alice_with_rng)
+    ];
 
-fn get_generic_error(text: &'static str) -> Error {
-    Error::BadLength {
-        name: text,
-        actual: 0,
-        expected: 0,
-    }
+    #[cfg(feature = "std")]
+    pub(crate) static ALICE_FROM_SEED_FP: [AliceFromSeedFn; 1] = [
+        box_alice_from_seed!(<crate::container::Container<crate::container::Firesaber>>::new_alice_from_seed)
+    ];
+
+    pub(crate) static DECAPSULATE_FP: [DecapsulateFn; 1] = [
+        crate::container::decapsulate::<crate::container::Firesaber>
+    ];
+
+    pub(crate) static IMPORT_FP: [ImportFn; 1] = [
+        box_import!(<crate::container::Container<crate::container::Firesaber>>::from_export)
+    ];
 }
-    /*
-create_struct!(PostQuantumAlgorithmData_babybear, babybear);
-create_struct!(PostQuantumAlgorithmData_babybearephem, babybearephem);
-*/
-create_struct!(PostQuantumAlgorithmData_firesaber, firesaber);
-/*
-create_struct!(PostQuantumAlgorithmData_frodokem640aes, frodokem640aes);
-create_struct!(PostQuantumAlgorithmData_frodokem640shake, frodokem640shake);
-create_struct!(PostQuantumAlgorithmData_frodokem976aes, frodokem976aes);
-create_struct!(PostQuantumAlgorithmData_frodokem976shake, frodokem976shake);
-create_struct!(PostQuantumAlgorithmData_frodokem1344aes, frodokem1344aes);
-create_struct!(PostQuantumAlgorithmData_frodokem1344shake, frodokem1344shake);
-
-create_struct!(PostQuantumAlgorithmData_kyber512, kyber512);
-create_struct!(PostQuantumAlgorithmData_kyber768, kyber768);
-create_struct!(PostQuantumAlgorithmData_kyber1024, kyber1024);
-create_struct!(PostQuantumAlgorithmData_kyber51290s, kyber51290s);
-create_struct!(PostQuantumAlgorithmData_kyber76890s, kyber76890s);
-create_struct!(PostQuantumAlgorithmData_kyber102490s, kyber102490s);
-
-create_struct!(PostQuantumAlgorithmData_ledakemlt12, ledakemlt12);
-create_struct!(PostQuantumAlgorithmData_ledakemlt32, ledakemlt32);
-create_struct!(PostQuantumAlgorithmData_ledakemlt52, ledakemlt52);
-
-create_struct!(PostQuantumAlgorithmData_lightsaber, lightsaber);
-
-create_struct!(PostQuantumAlgorithmData_mamabear, mamabear);
-create_struct!(PostQuantumAlgorithmData_mamabearephem, mamabearephem);
-
-create_struct!(PostQuantumAlgorithmData_mceliece348864, mceliece348864);
-create_struct!(PostQuantumAlgorithmData_mceliece348864f, mceliece348864f);
-create_struct!(PostQuantumAlgorithmData_mceliece460896, mceliece460896);
-create_struct!(PostQuantumAlgorithmData_mceliece460896f, mceliece460896f);
-create_struct!(PostQuantumAlgorithmData_mceliece6688128, mceliece6688128);
-create_struct!(PostQuantumAlgorithmData_mceliece6688128f, mceliece6688128f);
-create_struct!(PostQuantumAlgorithmData_mceliece6960119, mceliece6960119);
-create_struct!(PostQuantumAlgorithmData_mceliece6960119f, mceliece6960119f);
-create_struct!(PostQuantumAlgorithmData_mceliece8192128, mceliece8192128);
-create_struct!(PostQuantumAlgorithmData_mceliece8192128f, mceliece8192128f);
-
-create_struct!(PostQuantumAlgorithmData_newhope512cca, newhope512cca);
-create_struct!(PostQuantumAlgorithmData_newhope512cpa, newhope512cpa);
-create_struct!(PostQuantumAlgorithmData_newhope1024cca, newhope1024cca);
-create_struct!(PostQuantumAlgorithmData_newhope1024cpa, newhope1024cpa);
-
-create_struct!(PostQuantumAlgorithmData_ntruhps2048509, ntruhps2048509);
-create_struct!(PostQuantumAlgorithmData_ntruhps2048677, ntruhps2048677);
-create_struct!(PostQuantumAlgorithmData_ntruhps4096821, ntruhps4096821);
-
-create_struct!(PostQuantumAlgorithmData_ntruhrss701, ntruhrss701);
-
-create_struct!(PostQuantumAlgorithmData_papabear, papabear);
-create_struct!(PostQuantumAlgorithmData_papabearephem, papabearephem);
-create_struct!(PostQuantumAlgorithmData_saber, saber);*/
-}
\ No newline at end of file
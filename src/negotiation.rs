@@ -0,0 +1,210 @@
+use crate::algorithm_dictionary::{self, ALGORITHM_COUNT};
+use crate::ez_error::EzError;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// The version byte at the start of a serialized [Offer]
+pub const OFFER_VERSION: u8 = 1;
+
+/// A set of algorithm bytes, stored as a bitmap
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AlgorithmSet(u64);
+
+impl AlgorithmSet {
+    /// The set containing no algorithms
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// The set containing every algorithm this build implements (see [algorithm_dictionary::has_backend])
+    pub const fn all() -> Self {
+        let mut set = Self::empty();
+        let mut algorithm = 0;
+        while algorithm < ALGORITHM_COUNT {
+            if algorithm_dictionary::has_backend(algorithm) {
+                set = set.with(algorithm);
+            }
+
+            algorithm += 1;
+        }
+
+        set
+    }
+
+    /// Adds `algorithm` to the set. Bytes outside [algorithm_dictionary] name no algorithm, and are ignored
+    pub const fn with(self, algorithm: u8) -> Self {
+        if algorithm < ALGORITHM_COUNT {
+            Self(self.0 | (1u64 << algorithm))
+        } else {
+            self
+        }
+    }
+
+    /// Removes `algorithm` from the set
+    pub const fn without(self, algorithm: u8) -> Self {
+        if algorithm < ALGORITHM_COUNT {
+            Self(self.0 & !(1u64 << algorithm))
+        } else {
+            self
+        }
+    }
+
+    /// Returns true if `algorithm` is in the set
+    pub const fn contains(&self, algorithm: u8) -> bool {
+        algorithm < ALGORITHM_COUNT && self.0 & (1u64 << algorithm) != 0
+    }
+
+    /// The algorithms in the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item=u8> + '_ {
+        (0..ALGORITHM_COUNT).filter(move |algorithm| self.contains(*algorithm))
+    }
+}
+
+/// The local rules deciding which algorithms may be used, whether chosen locally, accepted from the other side or negotiated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    /// The algorithms that may be used at all
+    pub allowed: AlgorithmSet,
+    /// Algorithms that are never used, even if they are allowed
+    pub denied: AlgorithmSet,
+    /// The lowest acceptable NIST security category, from 1 to 5
+    pub min_nist_level: u8,
+    /// Whether algorithms that are only IND-CPA secure are rejected
+    pub require_ind_cca: bool,
+    /// The largest acceptable public key, in bytes, or None for no limit
+    pub max_public_key_bytes: Option<usize>
+}
+
+impl Default for Policy {
+    /// Permits every algorithm this build implements
+    fn default() -> Self {
+        Self { allowed: AlgorithmSet::all(), denied: AlgorithmSet::empty(), min_nist_level: 1, require_ind_cca: false, max_public_key_bytes: None }
+    }
+}
+
+impl Policy {
+    /// Returns true if the policy permits `algorithm` and this build implements it
+    pub fn permits(&self, algorithm: u8) -> bool {
+        algorithm_dictionary::has_backend(algorithm)
+            && self.allowed.contains(algorithm)
+            && !self.denied.contains(algorithm)
+            && matches!(algorithm_dictionary::nist_level(algorithm), Some(level) if level >= self.min_nist_level)
+            && (!self.require_ind_cca || algorithm_dictionary::is_ind_cca(algorithm))
+            && self.max_public_key_bytes.iter().all(|max| matches!(algorithm_dictionary::public_key_bytes(algorithm), Some(len) if len <= *max))
+    }
+
+    /// The algorithms the policy permits
+    pub fn permitted(&self) -> AlgorithmSet {
+        AlgorithmSet::all().iter().filter(|algorithm| self.permits(*algorithm)).fold(AlgorithmSet::empty(), AlgorithmSet::with)
+    }
+
+    /// Returns an error if this build does not implement `algorithm`, the policy does not permit it, or `public_key`
+    /// is longer than [Policy::max_public_key_bytes]. The limit is enforced on the key itself, not just on the length
+    /// the scheme specifies
+    pub(crate) fn check(&self, algorithm: u8, public_key: &[u8]) -> Result<(), EzError> {
+        crate::check_backend(algorithm)?;
+        if self.permits(algorithm) && self.max_public_key_bytes.iter().all(|max| public_key.len() <= *max) {
+            Ok(())
+        } else {
+            Err(EzError::PolicyViolation(algorithm))
+        }
+    }
+}
+
+/// The algorithms a peer is willing to use, most preferred first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Offer {
+    algorithms: Vec<u8>
+}
+
+impl Offer {
+    /// Offers `algorithms` in the given order of preference. Returns an error if the list is empty, or names an
+    /// algorithm twice or one this build does not support
+    pub fn new(algorithms: &[u8]) -> Result<Self, EzError> {
+        if algorithms.is_empty() {
+            return Err(EzError::InvalidInput("an offer must contain at least one algorithm"));
+        }
+
+        let mut seen = AlgorithmSet::empty();
+        for algorithm in algorithms.iter().copied() {
+            crate::check_algorithm(algorithm)?;
+            if seen.contains(algorithm) {
+                return Err(EzError::InvalidInput("an offer may not repeat an algorithm"));
+            }
+
+            seen = seen.with(algorithm);
+        }
+
+        Ok(Self { algorithms: algorithms.to_vec() })
+    }
+
+    /// Offers every algorithm `policy` permits, the highest NIST security category first
+    pub fn from_policy(policy: &Policy) -> Result<Self, EzError> {
+        let mut algorithms: Vec<u8> = policy.permitted().iter().collect();
+        algorithms.sort_by_key(|algorithm| core::cmp::Reverse(algorithm_dictionary::nist_level(*algorithm)));
+        if algorithms.is_empty() {
+            return Err(EzError::NegotiationFailed("the policy permits no algorithm"));
+        }
+
+        Ok(Self { algorithms })
+    }
+
+    /// The offered algorithms, most preferred first
+    pub fn algorithms(&self) -> &[u8] {
+        &self.algorithms
+    }
+
+    /// Serializes the offer as [OFFER_VERSION], the number of algorithms and the algorithm bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.algorithms.len());
+        bytes.push(OFFER_VERSION);
+        bytes.push(self.algorithms.len() as u8);
+        bytes.extend_from_slice(&self.algorithms);
+        bytes
+    }
+
+    /// Deserializes an offer produced by [Offer::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EzError> {
+        match bytes {
+            [OFFER_VERSION, count, algorithms @ ..] if *count as usize == algorithms.len() => Self::new(algorithms),
+            [OFFER_VERSION, count, algorithms @ ..] => Err(EzError::InvalidLength { what: "offer", expected: 2 + *count as usize, actual: 2 + algorithms.len() }),
+            [version, _, ..] => Err(EzError::UnsupportedVersion(*version)),
+            _ => Err(EzError::InvalidLength { what: "offer", expected: 2, actual: bytes.len() })
+        }
+    }
+}
+
+/// Picks the strongest algorithm this build implements that is present in both offers: the highest NIST security
+/// category, with ties broken by `chooser`'s order of preference. Both peers reach the same result from the same two
+/// offers. Returns [EzError::UnsupportedAlgorithm] if the offers only have algorithms without a backend in common
+pub fn select(chooser: &Offer, other: &Offer) -> Result<u8, EzError> {
+    let mut best: Option<u8> = None;
+    let mut unsupported: Option<u8> = None;
+    for algorithm in chooser.algorithms.iter().copied().filter(|algorithm| other.algorithms.contains(algorithm)) {
+        if !algorithm_dictionary::has_backend(algorithm) {
+            unsupported = unsupported.or(Some(algorithm));
+        } else if algorithm_dictionary::nist_level(algorithm) > best.and_then(algorithm_dictionary::nist_level) {
+            best = Some(algorithm);
+        }
+    }
+
+    match (best, unsupported) {
+        (Some(algorithm), _) => Ok(algorithm),
+        (None, Some(algorithm)) => Err(EzError::UnsupportedAlgorithm(algorithm)),
+        (None, None) => Err(EzError::NegotiationFailed("no algorithm is common to both offers"))
+    }
+}
+
+/// Hashes both offers and the choice. The hash is bound into the derived keys, so peers that saw different offers
+/// (i.e., an attacker tampered with them to force a weaker algorithm) end up with different keys
+pub(crate) fn transcript_hash(bob_offer: &Offer, alice_offer: &Offer, algorithm: u8) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"ez_pqcrypto negotiation");
+    hasher.update(bob_offer.to_bytes());
+    hasher.update(alice_offer.to_bytes());
+    hasher.update([algorithm]);
+
+    let mut transcript = [0u8; 32];
+    transcript.copy_from_slice(&hasher.finalize());
+    transcript
+}
@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+use zeroize::Zeroizing;
+#[cfg(feature = "chacha20")]
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+#[cfg(not(feature = "chacha20"))]
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{NewAead, Aead, Payload, generic_array::GenericArray}};
+use crate::NONCE_LENGTH_BYTES;
+use crate::export::AEAD_SUITE;
+use crate::ez_error::EzError;
+
+/// The magic bytes at the start of every password-encrypted container
+pub const ENCRYPTED_MAGIC: [u8; 4] = *b"EZPE";
+/// The current version of the password-encrypted format
+pub const ENCRYPTED_FORMAT_VERSION: u8 = 1;
+/// The length of the random salt stored with each encrypted container
+pub const SALT_LENGTH: usize = 16;
+
+/// The largest memory cost accepted when decrypting, so that a crafted file cannot demand unbounded memory
+pub const MAX_MEMORY_KIB: u32 = 1 << 20;
+/// The largest number of passes accepted when decrypting
+pub const MAX_ITERATIONS: u32 = 64;
+/// The largest degree of parallelism accepted when decrypting
+pub const MAX_PARALLELISM: u32 = 16;
+
+const KEY_LENGTH: usize = 32;
+/// magic, version, AEAD suite, the three cost parameters, salt and nonce
+const HEADER_LENGTH: usize = 4 + 1 + 1 + (3 * 4) + SALT_LENGTH + NONCE_LENGTH_BYTES;
+
+/// The Argon2id cost parameters used to derive the wrapping key. They are stored alongside the
+/// ciphertext, so only the password is needed to decrypt
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PasswordParams {
+    /// The memory size, in KiB
+    pub memory_kib: u32,
+    /// The number of passes over the memory
+    pub iterations: u32,
+    /// The degree of parallelism
+    pub parallelism: u32
+}
+
+impl Default for PasswordParams {
+    /// The Argon2 crate's recommended defaults: 19 MiB, two passes and one lane
+    fn default() -> Self {
+        Self { memory_kib: Params::DEFAULT_M_COST, iterations: Params::DEFAULT_T_COST, parallelism: Params::DEFAULT_P_COST }
+    }
+}
+
+impl PasswordParams {
+    fn check_limits(&self) -> Result<(), EzError> {
+        if self.memory_kib > MAX_MEMORY_KIB || self.iterations > MAX_ITERATIONS || self.parallelism > MAX_PARALLELISM {
+            Err(EzError::LimitExceeded("password hashing parameters"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, EzError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LENGTH)).map_err(|_| EzError::InvalidInput("invalid password hashing parameters"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params).hash_password_into(password, salt, &mut *key).map_err(|_| EzError::InvalidInput("invalid password hashing parameters"))?;
+        Ok(key)
+    }
+}
+
+/// Encrypts `plaintext` under a key derived from `password`. The header, including the parameters and
+/// salt, is authenticated as associated data
+pub(crate) fn encrypt(plaintext: &[u8], password: &[u8], params: &PasswordParams) -> Result<Vec<u8>, EzError> {
+    params.check_limits()?;
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH_BYTES];
+    crate::default_rng().fill_bytes(&mut salt);
+    crate::default_rng().fill_bytes(&mut nonce);
+
+    let mut output = Vec::with_capacity(HEADER_LENGTH + plaintext.len() + 16);
+    output.extend_from_slice(&ENCRYPTED_MAGIC);
+    output.push(ENCRYPTED_FORMAT_VERSION);
+    output.push(AEAD_SUITE);
+    output.extend_from_slice(&params.memory_kib.to_be_bytes());
+    output.extend_from_slice(&params.iterations.to_be_bytes());
+    output.extend_from_slice(&params.parallelism.to_be_bytes());
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+
+    let key = params.derive_key(password, &salt)?;
+    let mut aead = AeadKey::new(GenericArray::from_slice(&*key));
+    let ciphertext = aead.encrypt(GenericArray::from_slice(&nonce), Payload { msg: plaintext, aad: &output });
+    crate::wipe_aead_key(&mut aead);
+
+    output.extend_from_slice(&ciphertext.map_err(|_| EzError::EncryptionFailure)?);
+    Ok(output)
+}
+
+/// Decrypts the output of [encrypt]. A wrong password, or any tampering, yields [EzError::AuthenticationFailed]
+pub(crate) fn decrypt(bytes: &[u8], password: &[u8]) -> Result<Zeroizing<Vec<u8>>, EzError> {
+    if !bytes.starts_with(&ENCRYPTED_MAGIC) {
+        return Err(EzError::InvalidInput("not a password-encrypted container"));
+    }
+
+    if bytes.len() < HEADER_LENGTH {
+        return Err(EzError::InvalidLength { what: "encrypted container", expected: HEADER_LENGTH, actual: bytes.len() });
+    }
+
+    let (header, ciphertext) = bytes.split_at(HEADER_LENGTH);
+    if header[4] != ENCRYPTED_FORMAT_VERSION {
+        return Err(EzError::UnsupportedVersion(header[4]));
+    }
+
+    if header[5] != AEAD_SUITE {
+        return Err(EzError::UnsupportedAeadSuite(header[5]));
+    }
+
+    let read_u32 = |offset: usize| {
+        let mut array = [0u8; 4];
+        array.copy_from_slice(&header[offset..offset + 4]);
+        u32::from_be_bytes(array)
+    };
+
+    let params = PasswordParams { memory_kib: read_u32(6), iterations: read_u32(10), parallelism: read_u32(14) };
+    params.check_limits()?;
+    let salt = &header[18..18 + SALT_LENGTH];
+    let nonce = &header[18 + SALT_LENGTH..];
+
+    let key = params.derive_key(password, salt)?;
+    let mut aead = AeadKey::new(GenericArray::from_slice(&*key));
+    let plaintext = aead.decrypt(GenericArray::from_slice(nonce), Payload { msg: ciphertext, aad: header });
+    crate::wipe_aead_key(&mut aead);
+
+    plaintext.map(Zeroizing::new).map_err(|_| EzError::AuthenticationFailed)
+}
@@ -1,5 +1,5 @@
 pub mod ordered {
-    use std::sync::atomic::{AtomicU64, Ordering};
+    use core::sync::atomic::{AtomicU64, Ordering};
 
     /// Uses compare-and-swap operations to determine that an in-order packet is valid
     ///
@@ -15,11 +15,24 @@ pub mod ordered {
             self.out_counter.fetch_add(1, Ordering::SeqCst)
         }
 
+        /// Returns the inbound and outbound counters, so that they can be restored with [AntiReplayAttackContainerOrdered::from_counters]
+        pub fn get_counters(&self) -> (u64, u64) {
+            (self.in_counter.load(Ordering::SeqCst), self.out_counter.load(Ordering::SeqCst))
+        }
+
+        /// Resumes from previously saved counters
+        pub fn from_counters(in_counter: u64, out_counter: u64) -> Self {
+            Self { in_counter: AtomicU64::new(in_counter), out_counter: AtomicU64::new(out_counter) }
+        }
+
         /// Returns true if the value is valid, false otherwise
         #[inline]
         pub fn on_pid_received(&self, pid: u64) -> bool {
             //println!("PREV: {} | RECV: {}", self.in_counter.load(Ordering::Relaxed), pid);
-            self.in_counter.compare_and_swap(pid, pid + 1, Ordering::SeqCst) == pid
+            match pid.checked_add(1) {
+                Some(next) => self.in_counter.compare_exchange(pid, next, Ordering::SeqCst, Ordering::SeqCst).is_ok(),
+                None => false
+            }
         }
     }
 
@@ -67,8 +80,8 @@ pub mod unordered {
         #[allow(unused_results)]
         pub fn try_register(&self, pid_received: u64) -> Result<(), EzError> {
             let mut queue = self.history.lock();
-            if let Some(_) = queue.1.iter().find(|already_arrived| **already_arrived == pid_received) {
-                Err(EzError::Generic("Packet already arrived"))
+            if queue.1.iter().any(|already_arrived| *already_arrived == pid_received) {
+                Err(EzError::ReplayDetected)
             } else {
                 // this means the PID is not in the history. HOWEVER, it may still be possible that the packet
                 // was withheld long enough for the history to be cleared, thus enabling a delayed replay attack.
@@ -82,7 +95,7 @@ pub mod unordered {
                     queue.1.push(pid_received);
                     Ok(())
                 } else {
-                    Err(EzError::Generic("Packet already arrived"))
+                    Err(EzError::ReplayDetected)
                 }
             }
         }
@@ -0,0 +1,196 @@
+use std::io::{Read, Write};
+use std::io;
+use rand::RngCore;
+#[cfg(feature = "chacha20")]
+use chacha20poly1305::{XChaCha20Poly1305 as AeadKey, aead::{AeadInPlace, generic_array::GenericArray}};
+#[cfg(not(feature = "chacha20"))]
+use aes_gcm_siv::{Aes256GcmSiv as AeadKey, aead::{AeadInPlace, generic_array::GenericArray}};
+use crate::{PostQuantumContainer, NONCE_LENGTH_BYTES};
+use crate::ez_error::EzError;
+
+/// The number of plaintext bytes in every segment except the last
+pub const SEGMENT_LENGTH: usize = 64 * 1024;
+/// The length of the tag appended to each encrypted segment
+pub const TAG_LENGTH_BYTES: usize = 16;
+/// The length of the random nonce prefix written at the start of each stream. The
+/// remainder of each segment's nonce is a 32-bit big-endian counter and a one-byte last flag
+pub const NONCE_PREFIX_LENGTH: usize = NONCE_LENGTH_BYTES - 5;
+
+const ENCRYPTED_SEGMENT_LENGTH: usize = SEGMENT_LENGTH + TAG_LENGTH_BYTES;
+
+/// Builds the STREAM nonce for the segment at `counter`
+fn segment_nonce(prefix: &[u8; NONCE_PREFIX_LENGTH], counter: u32, last: bool) -> [u8; NONCE_LENGTH_BYTES] {
+    let mut nonce = [0u8; NONCE_LENGTH_BYTES];
+    nonce[..NONCE_PREFIX_LENGTH].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LENGTH..NONCE_LENGTH_BYTES - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[NONCE_LENGTH_BYTES - 1] = last as u8;
+    nonce
+}
+
+fn get_key(container: &PostQuantumContainer) -> io::Result<&AeadKey> {
+    container.get_aead().ok_or_else(|| into_io_error(EzError::HandshakeIncomplete { missing: "shared secret" }))
+}
+
+fn into_io_error(err: EzError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Encrypts an arbitrarily long plaintext using the STREAM online-AE construction. Plaintext
+/// is split into segments of [SEGMENT_LENGTH] bytes, and each segment is sealed under a nonce
+/// derived from a random per-stream prefix, the segment counter and a last-segment flag.
+///
+/// [EncryptStream::finish] MUST be called once all the plaintext has been written. Otherwise,
+/// the final segment is never emitted and the reader will treat the stream as truncated
+pub struct EncryptStream<'a, W: Write> {
+    key: &'a AeadKey,
+    inner: W,
+    prefix: [u8; NONCE_PREFIX_LENGTH],
+    counter: u32,
+    buffer: Vec<u8>
+}
+
+impl<'a, W: Write> EncryptStream<'a, W> {
+    /// Creates a new stream that writes ciphertext into `inner`. This writes the nonce prefix
+    /// immediately, and will return an error if the container's shared secret is not loaded
+    pub fn new(container: &'a PostQuantumContainer, mut inner: W) -> io::Result<Self> {
+        let key = get_key(container)?;
+        let mut prefix = [0u8; NONCE_PREFIX_LENGTH];
+        crate::default_rng().fill_bytes(&mut prefix);
+        inner.write_all(&prefix)?;
+
+        Ok(Self { key, inner, prefix, counter: 0, buffer: Vec::with_capacity(ENCRYPTED_SEGMENT_LENGTH) })
+    }
+
+    /// Seals the buffered segment and writes it to the inner writer
+    fn seal_segment(&mut self, last: bool) -> io::Result<()> {
+        let nonce = segment_nonce(&self.prefix, self.counter, last);
+        self.key.encrypt_in_place(GenericArray::from_slice(&nonce), &[], &mut self.buffer).map_err(|_| into_io_error(EzError::EncryptionFailure))?;
+        self.inner.write_all(&self.buffer)?;
+        self.buffer.clear();
+
+        if !last {
+            self.counter = self.counter.checked_add(1).ok_or_else(|| into_io_error(EzError::LimitExceeded("stream segment counter overflow")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Seals the final segment, flushes the inner writer and returns it. The final segment is
+    /// always shorter than a full segment, and may be empty
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.buffer.len() == SEGMENT_LENGTH {
+            self.seal_segment(false)?;
+        }
+
+        self.seal_segment(true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptStream<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+        while written < buf.len() {
+            // a full segment is only sealed once more data arrives, since it may end up being the last one
+            if self.buffer.len() == SEGMENT_LENGTH {
+                self.seal_segment(false)?;
+            }
+
+            let amt = (SEGMENT_LENGTH - self.buffer.len()).min(buf.len() - written);
+            self.buffer.extend_from_slice(&buf[written..written + amt]);
+            written += amt;
+        }
+
+        Ok(written)
+    }
+
+    /// Flushes the inner writer. Buffered plaintext is not emitted until its segment is complete
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts a stream produced by [EncryptStream]. Every segment is authenticated before any of
+/// its plaintext is returned. A stream that was truncated, reordered or extended fails to
+/// authenticate and yields an error
+pub struct DecryptStream<'a, R: Read> {
+    key: &'a AeadKey,
+    inner: R,
+    prefix: [u8; NONCE_PREFIX_LENGTH],
+    counter: u32,
+    buffer: Vec<u8>,
+    position: usize,
+    lookahead: Option<u8>,
+    finished: bool,
+    failed: bool
+}
+
+impl<'a, R: Read> DecryptStream<'a, R> {
+    /// Creates a new stream that reads ciphertext from `inner`. This reads the nonce prefix
+    /// immediately, and will return an error if the container's shared secret is not loaded
+    pub fn new(container: &'a PostQuantumContainer, mut inner: R) -> io::Result<Self> {
+        let key = get_key(container)?;
+        let mut prefix = [0u8; NONCE_PREFIX_LENGTH];
+        inner.read_exact(&mut prefix)?;
+
+        Ok(Self { key, inner, prefix, counter: 0, buffer: Vec::with_capacity(ENCRYPTED_SEGMENT_LENGTH + 1), position: 0, lookahead: None, finished: false, failed: false })
+    }
+
+    /// Returns the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads and authenticates the next segment into the internal buffer
+    fn open_segment(&mut self) -> io::Result<()> {
+        self.buffer.clear();
+        self.position = 0;
+        self.buffer.extend(self.lookahead.take());
+        // read one byte past a full segment to learn whether or not this is the last one
+        let remaining = ENCRYPTED_SEGMENT_LENGTH + 1 - self.buffer.len();
+        let _ = (&mut self.inner).take(remaining as u64).read_to_end(&mut self.buffer)?;
+
+        let last = self.buffer.len() <= ENCRYPTED_SEGMENT_LENGTH;
+        if !last {
+            self.lookahead = self.buffer.pop();
+        }
+
+        let nonce = segment_nonce(&self.prefix, self.counter, last);
+        if self.key.decrypt_in_place(GenericArray::from_slice(&nonce), &[], &mut self.buffer).is_err() {
+            // never release unauthenticated plaintext, and refuse to continue past a bad segment
+            self.buffer.clear();
+            self.failed = true;
+            return Err(into_io_error(EzError::AuthenticationFailed));
+        }
+
+        if last {
+            self.finished = true;
+        } else {
+            self.counter = self.counter.checked_add(1).ok_or_else(|| into_io_error(EzError::LimitExceeded("stream segment counter overflow")))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptStream<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.failed {
+            return Err(into_io_error(EzError::AuthenticationFailed));
+        }
+
+        if self.position == self.buffer.len() {
+            if self.finished {
+                return Ok(0);
+            }
+
+            self.open_segment()?;
+        }
+
+        let amt = (self.buffer.len() - self.position).min(buf.len());
+        buf[..amt].copy_from_slice(&self.buffer[self.position..self.position + amt]);
+        self.position += amt;
+        Ok(amt)
+    }
+}
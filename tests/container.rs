@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use ez_pqcrypto::container::{Algorithm, Container, Firesaber, Kem};
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, PQNode, NONCE_LENGTH_BYTES};
+
+    const NONCE: [u8; NONCE_LENGTH_BYTES] = [0u8; NONCE_LENGTH_BYTES];
+
+    fn exchange() -> (Container<Firesaber>, Container<Firesaber>) {
+        let mut alice_container = Container::<Firesaber>::new_alice();
+        let bob_container = Container::<Firesaber>::new_bob(alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        (alice_container, bob_container)
+    }
+
+    #[test]
+    fn static_exchange() {
+        assert_eq!(Firesaber::ALGORITHM, algorithm_dictionary::FIRESABER);
+        let (alice_container, bob_container) = exchange();
+        assert_eq!(alice_container.get_public_key().len(), Firesaber::PUBLIC_KEY_BYTES);
+        assert_eq!(bob_container.get_ciphertext().unwrap().len(), Firesaber::CIPHERTEXT_BYTES);
+        assert_eq!(alice_container.get_shared_secret().unwrap(), bob_container.get_shared_secret().unwrap());
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        let ciphertext = alice_container.encrypt(b"hello", NONCE).unwrap();
+        assert_eq!(bob_container.decrypt(&ciphertext, NONCE).unwrap(), b"hello");
+
+        let mut packet = BytesMut::from(&b"headerpayload"[..]);
+        bob_container.protect_packet_in_place(6, &mut packet, NONCE).unwrap();
+        let mut payload = packet.split_off(6);
+        alice_container.validate_packet_in_place(&packet, &mut payload, NONCE).unwrap();
+        assert_eq!(&payload[..], b"payload");
+    }
+
+    #[test]
+    fn interoperates_with_dynamic_container() {
+        let mut alice_container = Container::<Firesaber>::new_alice();
+        let bob_container = PostQuantumContainer::new_bob(Firesaber::ALGORITHM, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        let ciphertext = bob_container.encrypt(b"hello", NONCE).unwrap();
+        assert_eq!(alice_container.decrypt(&ciphertext, NONCE).unwrap(), b"hello");
+
+        // wrapping keeps the replay state, and the wrapper can be serialized
+        let alice_container = PostQuantumContainer::from(alice_container);
+        assert_eq!(alice_container.get_algorithm_idx(), algorithm_dictionary::FIRESABER);
+        assert_eq!(alice_container.get_node_type(), PQNode::Alice);
+        let restored = PostQuantumContainer::deserialize_from_bytes(alice_container.serialize_to_vector().unwrap()).unwrap();
+        assert!(bob_container.verify_key_confirmation(&restored.key_confirmation().unwrap()));
+    }
+
+    #[test]
+    fn wrong_role_and_order() {
+        let (mut alice_container, mut bob_container) = exchange();
+        let ciphertext = bob_container.get_ciphertext().unwrap().to_vec();
+        assert!(matches!(alice_container.alice_on_receive_ciphertext(&ciphertext), Err(EzError::InvalidInput(_))));
+        assert!(matches!(bob_container.alice_on_receive_ciphertext(&ciphertext), Err(EzError::WrongRole { .. })));
+        assert!(matches!(bob_container.get_secret_key(), Err(EzError::WrongRole { .. })));
+
+        let fresh_alice = Container::<Firesaber>::new_alice();
+        assert!(matches!(fresh_alice.encrypt(b"hello", NONCE), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(Container::<Firesaber>::new_bob(&[0u8; 3]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, NONCE_LENGTH_BYTES};
+    use ez_pqcrypto::envelope::{key_id, open, open_multi, seal_to, seal_to_many, MultiRecipientEnvelope, PostQuantumEnvelope, ENVELOPE_FORMAT_VERSION, ENVELOPE_MAGIC, MULTI_ENVELOPE_MAGIC};
+
+    #[test]
+    fn seal_and_open() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let plaintext = b"a file for the recipient";
+
+        let envelope = seal_to(recipient.get_public_key(), algorithm, plaintext).unwrap();
+        let serialized = envelope.serialize_to_vector();
+
+        let envelope = PostQuantumEnvelope::deserialize_from_bytes(&serialized).unwrap();
+        assert_eq!(envelope.get_algorithm_idx(), algorithm);
+        let decrypted = open(&envelope, recipient.get_secret_key().unwrap()).unwrap();
+        assert_eq!(&plaintext[..], decrypted.as_slice());
+    }
+
+    #[test]
+    fn open_with_wrong_key_fails() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let eve = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+
+        let envelope = seal_to(recipient.get_public_key(), algorithm, b"secret").unwrap();
+        assert!(open(&envelope, eve.get_secret_key().unwrap()).is_err());
+        assert!(open(&envelope, &[0u8; 4]).is_err());
+
+        // an algorithm without a backend is refused, not just one outside the dictionary
+        let saber = algorithm_dictionary::SABER;
+        assert!(matches!(seal_to(recipient.get_public_key(), saber, b"secret"), Err(EzError::UnsupportedAlgorithm(algorithm)) if algorithm == saber));
+    }
+
+    #[test]
+    fn tampered_envelope_fails() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let serialized = seal_to(recipient.get_public_key(), algorithm, b"secret").unwrap().serialize_to_vector();
+
+        for idx in &[20, serialized.len() - 1] {
+            let mut tampered = serialized.clone();
+            tampered[*idx] ^= 1;
+            let envelope = PostQuantumEnvelope::deserialize_from_bytes(&tampered).unwrap();
+            assert!(open(&envelope, recipient.get_secret_key().unwrap()).is_err());
+        }
+
+        assert!(PostQuantumEnvelope::deserialize_from_bytes(&serialized[..10]).is_err());
+    }
+
+    #[test]
+    fn envelope_format_is_versioned() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let serialized = seal_to(recipient.get_public_key(), algorithm, b"secret").unwrap().serialize_to_vector();
+        assert_eq!(&serialized[..4], &ENVELOPE_MAGIC);
+        assert_eq!(serialized[4], ENVELOPE_FORMAT_VERSION);
+
+        let mut newer = serialized.clone();
+        newer[4] += 1;
+        assert!(matches!(PostQuantumEnvelope::deserialize_from_bytes(&newer), Err(EzError::UnsupportedVersion(version)) if version == ENVELOPE_FORMAT_VERSION + 1));
+        assert!(matches!(PostQuantumEnvelope::deserialize_from_bytes(&serialized[5..]), Err(EzError::InvalidInput(_))));
+        assert!(matches!(PostQuantumEnvelope::deserialize_from_bytes(&serialized[..4]), Err(EzError::InvalidLength { .. })));
+    }
+
+    #[test]
+    fn multi_recipient_envelope() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipients = (0..3).map(|_| PostQuantumContainer::new_alice(Some(algorithm)).unwrap()).collect::<Vec<_>>();
+        let outsider = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let public_keys = recipients.iter().map(|r| (algorithm, r.get_public_key())).collect::<Vec<_>>();
+
+        let serialized = seal_to_many(&public_keys, b"group broadcast").unwrap().serialize_to_vector();
+        let envelope = MultiRecipientEnvelope::deserialize_from_bytes(&serialized).unwrap();
+        assert_eq!(envelope.get_recipients().len(), 3);
+        assert_eq!(&serialized[..4], &MULTI_ENVELOPE_MAGIC);
+        let mut newer = serialized.clone();
+        newer[4] = ENVELOPE_FORMAT_VERSION + 1;
+        assert!(matches!(MultiRecipientEnvelope::deserialize_from_bytes(&newer), Err(EzError::UnsupportedVersion(_))));
+        // the two envelope formats cannot be mistaken for each other
+        assert!(matches!(PostQuantumEnvelope::deserialize_from_bytes(&serialized), Err(EzError::InvalidInput(_))));
+
+        for (entry, recipient) in envelope.get_recipients().iter().zip(recipients.iter()) {
+            assert_eq!(entry.get_key_id(), &key_id(recipient.get_public_key())[..]);
+            let decrypted = open_multi(&envelope, recipient.get_public_key(), recipient.get_secret_key().unwrap()).unwrap();
+            assert_eq!(decrypted.as_slice(), b"group broadcast");
+        }
+
+        assert!(open_multi(&envelope, outsider.get_public_key(), outsider.get_secret_key().unwrap()).is_err());
+        // a matching key ID alone is not enough
+        assert!(open_multi(&envelope, recipients[0].get_public_key(), outsider.get_secret_key().unwrap()).is_err());
+        assert!(seal_to_many(&[], b"nobody").is_err());
+        let saber = algorithm_dictionary::SABER;
+        assert!(matches!(seal_to_many(&[public_keys[0], (saber, public_keys[1].1)], b"group"), Err(EzError::UnsupportedAlgorithm(algorithm)) if algorithm == saber));
+
+        let mut tampered = serialized.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        let envelope = MultiRecipientEnvelope::deserialize_from_bytes(&tampered).unwrap();
+        assert!(open_multi(&envelope, recipients[1].get_public_key(), recipients[1].get_secret_key().unwrap()).is_err());
+    }
+
+    #[test]
+    fn recipient_list_is_authenticated() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipients = (0..3).map(|_| PostQuantumContainer::new_alice(Some(algorithm)).unwrap()).collect::<Vec<_>>();
+        let public_keys = recipients.iter().map(|r| (algorithm, r.get_public_key())).collect::<Vec<_>>();
+        let plaintext = b"group broadcast";
+        let serialized = seal_to_many(&public_keys, plaintext).unwrap().serialize_to_vector();
+
+        // the magic and version, the entry count, the equally sized entries, then the length-prefixed nonce and body
+        // (with its 16-byte tag)
+        let head = 4 + 1 + 8;
+        let tail = 8 + NONCE_LENGTH_BYTES + 8 + plaintext.len() + 16;
+        let entry_len = (serialized.len() - head - tail) / 3;
+        let entry = |idx: usize| &serialized[head + (idx * entry_len)..head + ((idx + 1) * entry_len)];
+        let rebuild = |order: &[usize]| {
+            let mut bytes = serialized[..5].to_vec();
+            bytes.extend_from_slice(&(order.len() as u64).to_le_bytes());
+            order.iter().for_each(|idx| bytes.extend_from_slice(entry(*idx)));
+            bytes.extend_from_slice(&serialized[serialized.len() - tail..]);
+            MultiRecipientEnvelope::deserialize_from_bytes(&bytes).unwrap()
+        };
+
+        let open_as = |envelope: &MultiRecipientEnvelope, idx: usize| open_multi(envelope, recipients[idx].get_public_key(), recipients[idx].get_secret_key().unwrap());
+        assert_eq!(open_as(&rebuild(&[0, 1, 2]), 0).unwrap().as_slice(), plaintext);
+        // dropping or reordering entries leaves each remaining entry intact, but the body no longer decrypts
+        assert!(open_as(&rebuild(&[0, 1]), 0).is_err());
+        assert!(open_as(&rebuild(&[1, 0, 2]), 0).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::export::{EXPORT_FORMAT_VERSION, EXPORT_MAGIC};
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer};
+    #[cfg(any(feature = "std", not(feature = "chacha20")))]
+    use ez_pqcrypto::PQNode;
+
+    // Serialized by version 1 of the format after Alice protected one packet and Bob validated it
+    const V1_ALICE: &[u8] = include_bytes!("golden/v1_alice.bin");
+    const V1_BOB: &[u8] = include_bytes!("golden/v1_bob.bin");
+    // The same exchange, serialized in the original unversioned format before any packets were sent. Reading it requires std
+    #[cfg(feature = "std")]
+    const LEGACY_ALICE: &[u8] = include_bytes!("golden/legacy_alice.bin");
+    #[cfg(feature = "std")]
+    const LEGACY_BOB: &[u8] = include_bytes!("golden/legacy_bob.bin");
+
+    /// The offset of the first field, after the fixed-size header
+    const FIELDS_OFFSET: usize = 24;
+    /// The length of the empty end field that closes every serialization
+    const END_FIELD_LENGTH: usize = 5;
+
+    fn with_field(serialized: &[u8], tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut output = serialized[..serialized.len() - END_FIELD_LENGTH].to_vec();
+        output.push(tag);
+        output.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+        output.extend_from_slice(contents);
+        output.extend_from_slice(&serialized[serialized.len() - END_FIELD_LENGTH..]);
+        output
+    }
+
+    #[test]
+    fn header_layout() {
+        for golden in [V1_ALICE, V1_BOB] {
+            assert_eq!(&golden[..4], &EXPORT_MAGIC);
+            assert_eq!(golden[4], EXPORT_FORMAT_VERSION);
+            assert_eq!(golden[5], algorithm_dictionary::FIRESABER);
+            assert_eq!(golden[FIELDS_OFFSET], 0x01);
+            assert_eq!(&golden[golden.len() - END_FIELD_LENGTH..], &[0u8; END_FIELD_LENGTH]);
+        }
+
+        assert_eq!(V1_ALICE[7], 0);
+        assert_eq!(V1_BOB[7], 1);
+    }
+
+    #[cfg(not(feature = "chacha20"))]
+    #[test]
+    fn golden_round_trip() {
+        for golden in [V1_ALICE, V1_BOB] {
+            let container = PostQuantumContainer::deserialize_from_bytes(golden).unwrap();
+            assert_eq!(container.serialize_to_vector().unwrap().as_slice(), golden);
+        }
+    }
+
+    #[cfg(not(feature = "chacha20"))]
+    #[test]
+    fn golden_contents() {
+        use bytes::BytesMut;
+        use ez_pqcrypto::NONCE_LENGTH_BYTES;
+
+        let alice_container = PostQuantumContainer::deserialize_from_bytes(V1_ALICE).unwrap();
+        let bob_container = PostQuantumContainer::deserialize_from_bytes(V1_BOB).unwrap();
+        assert_eq!(alice_container.get_node_type(), PQNode::Alice);
+        assert_eq!(bob_container.get_node_type(), PQNode::Bob);
+        assert_eq!(alice_container.get_algorithm_idx(), algorithm_dictionary::FIRESABER);
+        assert_eq!(alice_container.public_key_fingerprint(), bob_container.public_key_fingerprint());
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        // the replay state was saved after the first packet, so the next one must carry the second PID
+        let nonce = [0u8; NONCE_LENGTH_BYTES];
+        let mut packet = BytesMut::from(&b"headerpayload"[..]);
+        alice_container.protect_packet_in_place(6, &mut packet, nonce).unwrap();
+        let mut payload = packet.split_off(6);
+        bob_container.validate_packet_in_place(&packet, &mut payload, nonce).unwrap();
+        assert_eq!(&payload[..], b"payload");
+
+        #[cfg(feature = "std")]
+        {
+            let fresh_bob = PostQuantumContainer::deserialize_from_bytes(LEGACY_BOB).unwrap();
+            let mut packet = BytesMut::from(&b"headerpayload"[..]);
+            alice_container.protect_packet_in_place(6, &mut packet, nonce).unwrap();
+            let mut payload = packet.split_off(6);
+            assert!(matches!(fresh_bob.validate_packet_in_place(&packet, &mut payload, nonce), Err(EzError::ReplayDetected)));
+        }
+    }
+
+    #[cfg(feature = "chacha20")]
+    #[test]
+    fn golden_suite_mismatch() {
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(V1_ALICE), Err(EzError::UnsupportedAeadSuite(0))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn legacy_format_still_decodes() {
+        let alice_container = PostQuantumContainer::deserialize_from_bytes(LEGACY_ALICE).unwrap();
+        let bob_container = PostQuantumContainer::deserialize_from_bytes(LEGACY_BOB).unwrap();
+        assert_eq!(alice_container.get_node_type(), PQNode::Alice);
+        assert_eq!(bob_container.get_node_type(), PQNode::Bob);
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        // re-serializing upgrades to the current version
+        let upgraded = alice_container.serialize_to_vector().unwrap();
+        assert_eq!(&upgraded[..4], &EXPORT_MAGIC);
+        assert_eq!(upgraded[FIELDS_OFFSET..], V1_ALICE[FIELDS_OFFSET..]);
+    }
+
+    #[test]
+    fn unknown_versions_and_fields() {
+        let container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let serialized = container.serialize_to_vector().unwrap();
+
+        let mut future_version = serialized.clone();
+        future_version[4] = EXPORT_FORMAT_VERSION + 1;
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&future_version), Err(EzError::UnsupportedVersion(version)) if version == EXPORT_FORMAT_VERSION + 1));
+
+        let mut unknown_suite = serialized.clone();
+        unknown_suite[6] = 0xEE;
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&unknown_suite), Err(EzError::UnsupportedAeadSuite(0xEE))));
+
+        // fields added later are skipped by this reader, unless they are marked critical
+        let optional_field = with_field(&serialized, 0x42, b"from the future");
+        let restored = PostQuantumContainer::deserialize_from_bytes(&optional_field).unwrap();
+        assert_eq!(restored.serialize_to_vector().unwrap(), serialized);
+
+        let critical_field = with_field(&serialized, 0xC2, b"from the future");
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&critical_field), Err(EzError::InvalidInput(_))));
+
+        let duplicate_field = with_field(&serialized, 0x01, container.get_public_key());
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&duplicate_field), Err(EzError::InvalidInput(_))));
+
+        let mut trailing = serialized.clone();
+        trailing.push(0);
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&trailing), Err(EzError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,231 @@
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+    #[cfg(feature = "std")]
+    use std::io::IoSlice;
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::{algorithm_dictionary, hpke, PostQuantumContainer, Tag, NONCE_LENGTH_BYTES};
+    #[cfg(feature = "std")]
+    use ez_pqcrypto::envelope;
+
+    const ALGORITHM: u8 = algorithm_dictionary::FIRESABER;
+    /// Corrupting every byte of a serialized key is slow in debug builds, so only every n-th byte (plus the leading fields) is flipped
+    const FLIP_STRIDE: usize = 37;
+
+    fn exchange() -> (PostQuantumContainer, PostQuantumContainer) {
+        let mut alice_container = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(ALGORITHM, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        (alice_container, bob_container)
+    }
+
+    /// Every length from empty up to twice the expected length, except the expected one
+    fn wrong_lengths(expected: usize) -> impl Iterator<Item=usize> {
+        (0..=expected * 2).filter(move |len| *len != expected)
+    }
+
+    #[test]
+    fn unsupported_algorithms() {
+        let public_key = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap().get_public_key().to_vec();
+        for algorithm in algorithm_dictionary::ALGORITHM_COUNT..=u8::MAX {
+            assert!(matches!(PostQuantumContainer::new_alice(Some(algorithm)), Err(EzError::UnsupportedAlgorithm(val)) if val == algorithm));
+            assert!(matches!(PostQuantumContainer::new_bob(algorithm, &public_key), Err(EzError::UnsupportedAlgorithm(_))));
+            #[cfg(feature = "std")]
+            assert!(envelope::seal_to(&public_key, algorithm, b"data").is_err());
+            assert!(hpke::setup_base_s(algorithm, &public_key, &[]).is_err());
+        }
+    }
+
+    #[test]
+    fn malformed_keys_and_ciphertexts() {
+        let mut alice_container = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        let public_key = alice_container.get_public_key().to_vec();
+        let secret_key = alice_container.get_secret_key().unwrap().to_vec();
+
+        for len in [0, 1, public_key.len() - 1, public_key.len() + 1] {
+            assert!(matches!(PostQuantumContainer::new_bob(ALGORITHM, &vec![0xAB; len]), Err(EzError::InvalidLength { .. })));
+        }
+
+        let bob_container = PostQuantumContainer::new_bob(ALGORITHM, &public_key).unwrap();
+        let ciphertext = bob_container.get_ciphertext().unwrap().to_vec();
+        for len in [0, 1, ciphertext.len() - 1, ciphertext.len() + 1] {
+            assert!(matches!(alice_container.alice_on_receive_ciphertext(&vec![0xAB; len]), Err(EzError::InvalidLength { .. })));
+            assert!(hpke::setup_base_r(ALGORITHM, &vec![0xAB; len], &secret_key, &public_key, &[]).is_err());
+        }
+
+        // a garbage ciphertext of the right length implicitly rejects: it yields a different shared secret
+        let mut garbage_alice = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        garbage_alice.alice_on_receive_ciphertext(&vec![0xAB; ciphertext.len()]).unwrap();
+        alice_container.alice_on_receive_ciphertext(&ciphertext).unwrap();
+        assert!(!garbage_alice.shared_secret_eq(bob_container.get_shared_secret().unwrap()));
+        assert!(alice_container.shared_secret_eq(bob_container.get_shared_secret().unwrap()));
+    }
+
+    #[test]
+    fn wrong_role_and_order() {
+        let (mut alice_container, mut bob_container) = exchange();
+        let ciphertext = bob_container.get_ciphertext().unwrap().to_vec();
+
+        assert!(matches!(bob_container.alice_on_receive_ciphertext(&ciphertext), Err(EzError::WrongRole { .. })));
+        assert!(matches!(alice_container.alice_on_receive_ciphertext(&ciphertext), Err(EzError::InvalidInput(_))));
+        assert!(matches!(bob_container.get_secret_key(), Err(EzError::WrongRole { .. })));
+
+        let early = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        let nonce = [0u8; NONCE_LENGTH_BYTES];
+        assert!(matches!(early.encrypt(b"data", nonce), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(matches!(early.decrypt(b"data", nonce), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(matches!(early.key_confirmation(), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(matches!(early.export_keying_material(b"label", &[], 32), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(!early.verify_key_confirmation(&[0u8; 32]));
+        assert!(matches!(alice_container.export_keying_material(b"label", &[], usize::MAX), Err(EzError::LimitExceeded(_))));
+    }
+
+    #[test]
+    fn wrong_nonce_lengths() {
+        let (alice_container, bob_container) = exchange();
+        let ciphertext = alice_container.encrypt(b"data", [0u8; NONCE_LENGTH_BYTES]).unwrap();
+        let tag = Tag::default();
+
+        for len in wrong_lengths(NONCE_LENGTH_BYTES) {
+            let nonce = vec![0u8; len];
+            let is_nonce_error = |result: Result<(), EzError>| matches!(result, Err(EzError::InvalidLength { what: "nonce", .. }));
+
+            assert!(is_nonce_error(alice_container.encrypt(b"data", &nonce).map(|_| ())));
+            assert!(is_nonce_error(bob_container.decrypt(&ciphertext, &nonce).map(|_| ())));
+            assert!(is_nonce_error(alice_container.encrypt_detached(b"data", &nonce).map(|_| ())));
+            assert!(is_nonce_error(bob_container.decrypt_detached(b"data", &tag, &nonce).map(|_| ())));
+
+            let mut packet = BytesMut::new();
+            packet.put_slice(b"headerpayload");
+            assert!(is_nonce_error(alice_container.protect_packet_in_place(6, &mut packet, &nonce)));
+            assert!(is_nonce_error(bob_container.validate_packet_in_place(b"header", &mut packet, &nonce)));
+            assert!(is_nonce_error(alice_container.protect_packet_in_place_detached(6, &mut packet, &nonce).map(|_| ())));
+            assert!(is_nonce_error(bob_container.validate_packet_in_place_detached(b"header", &mut packet, &tag, &nonce)));
+
+            let mut buffer = [0u8; 64];
+            assert!(is_nonce_error(alice_container.protect_packet_in_slice(6, &mut buffer, 13, &nonce).map(|_| ())));
+            assert!(is_nonce_error(bob_container.validate_packet_in_slice(b"header", &mut buffer, &nonce).map(|_| ())));
+
+            #[cfg(feature = "std")]
+            {
+                let mut payload = *b"payload";
+                assert!(is_nonce_error(alice_container.protect_packet_vectored(&[IoSlice::new(b"header")], &mut payload, &nonce).map(|_| ())));
+                assert!(is_nonce_error(bob_container.validate_packet_vectored(&[IoSlice::new(b"header")], &mut payload, &tag, 0, &nonce)));
+            }
+        }
+    }
+
+    #[test]
+    fn bad_packet_bounds() {
+        let (alice_container, bob_container) = exchange();
+        let nonce = [0u8; NONCE_LENGTH_BYTES];
+
+        let mut packet = BytesMut::new();
+        packet.put_slice(b"short");
+        assert!(alice_container.protect_packet_in_place(6, &mut packet, nonce).is_err());
+        assert!(alice_container.protect_packet_in_place_detached(6, &mut packet, nonce).is_err());
+        assert_eq!(&packet[..], b"short");
+
+        let mut buffer = [0u8; 16];
+        assert!(alice_container.protect_packet_in_slice(8, &mut buffer, 4, nonce).is_err());
+        assert!(alice_container.protect_packet_in_slice(0, &mut buffer, 17, nonce).is_err());
+        assert!(matches!(alice_container.protect_packet_in_slice(0, &mut buffer, 4, nonce), Err(EzError::InsufficientCapacity { .. })));
+
+        // too short to hold a tag, let alone a PID
+        for len in 0..24 {
+            let mut payload = BytesMut::from(&vec![0u8; len][..]);
+            assert!(bob_container.validate_packet_in_place(b"", &mut payload, nonce).is_err());
+            assert!(bob_container.validate_packet_in_slice(b"", &mut vec![0u8; len], nonce).is_err());
+        }
+    }
+
+    #[test]
+    fn corrupt_serialized_containers() {
+        let (alice_container, _) = exchange();
+        let serialized = alice_container.serialize_to_vector().unwrap();
+
+        // every truncation, and a flipped byte at any position, must either fail cleanly or produce a container
+        for len in 0..serialized.len() {
+            assert!(PostQuantumContainer::deserialize_from_bytes(&serialized[..len]).is_err());
+        }
+
+        for idx in (0..serialized.len()).step_by(FLIP_STRIDE).chain(0..16) {
+            let mut corrupt = serialized.clone();
+            corrupt[idx] ^= 0xFF;
+            let _ = PostQuantumContainer::deserialize_from_bytes(&corrupt);
+        }
+
+        // a length prefix claiming far more data than is present must not be trusted for allocation
+        let mut huge_length = serialized.clone();
+        huge_length[25..29].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&huge_length), Err(EzError::InvalidLength { .. })));
+
+        let mut bad_algorithm = serialized.clone();
+        bad_algorithm[5] = u8::MAX;
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&bad_algorithm), Err(EzError::UnsupportedAlgorithm(u8::MAX))));
+
+        let mut bad_node = serialized.clone();
+        bad_node[7] = 7;
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&bad_node), Err(EzError::InvalidInput(_))));
+
+        // the unversioned format is still accepted, so its length prefixes must be bounded too
+        let mut legacy_huge_length = include_bytes!("golden/legacy_alice.bin").to_vec();
+        legacy_huge_length[1..9].copy_from_slice(&u64::MAX.to_le_bytes());
+        #[cfg(feature = "std")]
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&legacy_huge_length), Err(EzError::DeserializeError { .. })));
+        // without std the unversioned format is refused outright
+        #[cfg(not(feature = "std"))]
+        assert!(matches!(PostQuantumContainer::deserialize_from_bytes(&legacy_huge_length), Err(EzError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn serialized_alice_before_exchange() {
+        let alice_container = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        let mut restored = PostQuantumContainer::deserialize_from_bytes(alice_container.serialize_to_vector().unwrap()).unwrap();
+        assert!(restored.get_ciphertext().is_err());
+        assert!(restored.encrypt(b"data", [0u8; NONCE_LENGTH_BYTES]).is_err());
+
+        let bob_container = PostQuantumContainer::new_bob(ALGORITHM, restored.get_public_key()).unwrap();
+        restored.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        assert!(restored.shared_secret_eq(bob_container.get_shared_secret().unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn corrupt_envelopes() {
+        let recipient = PostQuantumContainer::new_alice(Some(ALGORITHM)).unwrap();
+        let public_key = recipient.get_public_key();
+        let secret_key = recipient.get_secret_key().unwrap();
+
+        let serialized = envelope::seal_to(public_key, ALGORITHM, b"data").unwrap().serialize_to_vector();
+        for len in 0..serialized.len() {
+            assert!(envelope::PostQuantumEnvelope::deserialize_from_bytes(&serialized[..len]).is_err());
+        }
+
+        let mut huge_length = serialized.clone();
+        huge_length[1..9].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(envelope::PostQuantumEnvelope::deserialize_from_bytes(&huge_length).is_err());
+
+        for idx in (0..serialized.len()).step_by(FLIP_STRIDE).chain(0..16) {
+            let mut corrupt = serialized.clone();
+            corrupt[idx] ^= 0xFF;
+            if let Ok(corrupt) = envelope::PostQuantumEnvelope::deserialize_from_bytes(&corrupt) {
+                assert!(envelope::open(&corrupt, secret_key).is_err());
+            }
+        }
+
+        let multi = envelope::seal_to_many(&[(ALGORITHM, public_key)], b"data").unwrap().serialize_to_vector();
+        let mut huge_count = multi.clone();
+        huge_count[..8].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        assert!(envelope::MultiRecipientEnvelope::deserialize_from_bytes(&huge_count).is_err());
+
+        for idx in (0..multi.len()).step_by(FLIP_STRIDE).chain(0..16) {
+            let mut corrupt = multi.clone();
+            corrupt[idx] ^= 0xFF;
+            if let Ok(corrupt) = envelope::MultiRecipientEnvelope::deserialize_from_bytes(&corrupt) {
+                assert!(envelope::open_multi(&corrupt, public_key, secret_key).is_err());
+            }
+        }
+    }
+}
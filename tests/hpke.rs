@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer};
+    use ez_pqcrypto::hpke::{setup_base_r, setup_base_s, setup_psk_r, setup_psk_s};
+
+    const INFO: &[u8] = b"Ode on a Grecian Urn";
+
+    #[test]
+    fn hpke_base_mode() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let pk = recipient.get_public_key();
+        let sk = recipient.get_secret_key().unwrap();
+
+        let (enc, mut sender) = setup_base_s(algorithm, pk, INFO).unwrap();
+        let mut receiver = setup_base_r(algorithm, &enc, sk, pk, INFO).unwrap();
+
+        for idx in 0..3u8 {
+            let aad = [idx];
+            let ciphertext = sender.seal(&aad, b"beauty is truth, truth beauty").unwrap();
+            assert!(receiver.open(&[idx + 1], &ciphertext).is_err());
+            assert_eq!(receiver.open(&aad, &ciphertext).unwrap().as_slice(), b"beauty is truth, truth beauty");
+            // the sequence number advanced, so the same ciphertext can't be opened twice
+            assert!(receiver.open(&aad, &ciphertext).is_err());
+        }
+
+        assert_eq!(sender.export(b"context", 42).unwrap(), receiver.export(b"context", 42).unwrap());
+        assert_ne!(sender.export(b"context", 32).unwrap(), sender.export(b"other context", 32).unwrap());
+    }
+
+    #[test]
+    fn hpke_psk_mode() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let pk = recipient.get_public_key();
+        let sk = recipient.get_secret_key().unwrap();
+        let psk = [0x55u8; 32];
+
+        let (enc, mut sender) = setup_psk_s(algorithm, pk, INFO, &psk, b"psk id").unwrap();
+        let ciphertext = sender.seal(&[], b"hello").unwrap();
+
+        let mut receiver = setup_psk_r(algorithm, &enc, sk, pk, INFO, &psk, b"psk id").unwrap();
+        assert_eq!(receiver.open(&[], &ciphertext).unwrap().as_slice(), b"hello");
+
+        let mut wrong_psk = setup_psk_r(algorithm, &enc, sk, pk, INFO, &[0x56u8; 32], b"psk id").unwrap();
+        assert!(wrong_psk.open(&[], &ciphertext).is_err());
+        let mut base = setup_base_r(algorithm, &enc, sk, pk, INFO).unwrap();
+        assert!(base.open(&[], &ciphertext).is_err());
+
+        assert!(setup_psk_s(algorithm, pk, INFO, &[], b"psk id").is_err());
+        assert!(setup_psk_s(algorithm, pk, INFO, &psk, &[]).is_err());
+    }
+
+    #[test]
+    fn hpke_context_binding() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let recipient = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let pk = recipient.get_public_key();
+        let sk = recipient.get_secret_key().unwrap();
+
+        let (enc, mut sender) = setup_base_s(algorithm, pk, INFO).unwrap();
+        let ciphertext = sender.seal(&[], b"hello").unwrap();
+
+        let mut wrong_info = setup_base_r(algorithm, &enc, sk, pk, b"another poem").unwrap();
+        assert!(wrong_info.open(&[], &ciphertext).is_err());
+
+        let mut tampered_enc = enc.clone();
+        tampered_enc[0] ^= 1;
+        let mut wrong_enc = setup_base_r(algorithm, &tampered_enc, sk, pk, INFO).unwrap();
+        assert!(wrong_enc.open(&[], &ciphertext).is_err());
+
+        assert!(setup_base_s(algorithm_dictionary::ALGORITHM_COUNT, pk, INFO).is_err());
+        assert!(setup_base_s(algorithm_dictionary::SABER, pk, INFO).is_err());
+        assert!(setup_base_r(algorithm_dictionary::SABER, &enc, sk, pk, INFO).is_err());
+        assert!(setup_base_r(algorithm, &enc[1..], sk, pk, INFO).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use ez_pqcrypto::algorithm_dictionary::*;
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::negotiation::{select, AlgorithmSet, Offer, Policy};
+    use ez_pqcrypto::{PostQuantumContainer, NONCE_LENGTH_BYTES};
+
+    const NONCE: [u8; NONCE_LENGTH_BYTES] = [0u8; NONCE_LENGTH_BYTES];
+
+    /// Runs the exchange, with `tamper` applied to Bob's offer on its way to Alice
+    fn negotiate(bob_policy: &Policy, alice_policy: &Policy, tamper: impl FnOnce(Offer) -> Offer) -> Result<(PostQuantumContainer, PostQuantumContainer), EzError> {
+        let bob_offer = Offer::from_policy(bob_policy)?;
+        let received = Offer::from_bytes(&tamper(bob_offer.clone()).to_bytes())?;
+
+        let (mut alice_container, alice_offer) = PostQuantumContainer::new_alice_negotiated(&received, alice_policy)?;
+        let alice_offer = Offer::from_bytes(&alice_offer.to_bytes())?;
+        let bob_container = PostQuantumContainer::new_bob_negotiated(&bob_offer, &alice_offer, bob_policy, alice_container.get_algorithm_idx(), alice_container.get_public_key())?;
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext()?)?;
+        Ok((alice_container, bob_container))
+    }
+
+    #[test]
+    fn picks_strongest_common_algorithm() {
+        let (alice_container, bob_container) = negotiate(&Policy::default(), &Policy::default(), |offer| offer).unwrap();
+        assert_eq!(alice_container.get_algorithm_idx(), FIRESABER);
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        let ciphertext = alice_container.encrypt(b"hello", NONCE).unwrap();
+        assert_eq!(bob_container.decrypt(&ciphertext, NONCE).unwrap(), b"hello");
+
+        // the binding survives serialization
+        let restored = PostQuantumContainer::deserialize_from_bytes(bob_container.serialize_to_vector().unwrap()).unwrap();
+        assert_eq!(restored.decrypt(&ciphertext, NONCE).unwrap(), b"hello");
+
+        // the negotiated keys differ from those of a plain exchange over the same shared secret
+        let plain = PostQuantumContainer::new_bob(FIRESABER, bob_container.get_public_key()).unwrap();
+        assert!(!plain.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+
+        // algorithms without a backend are skipped, and are an error if nothing else is common
+        assert_eq!(select(&Offer::new(&[LIGHTSABER, FIRESABER]).unwrap(), &Offer::new(&[KYBER512, LIGHTSABER, FIRESABER]).unwrap()).unwrap(), FIRESABER);
+        assert!(matches!(select(&Offer::new(&[SABER, KYBER768]).unwrap(), &Offer::new(&[KYBER768, SABER]).unwrap()), Err(EzError::UnsupportedAlgorithm(SABER))));
+        assert!(matches!(select(&Offer::new(&[SABER]).unwrap(), &Offer::new(&[FIRESABER]).unwrap()), Err(EzError::NegotiationFailed(_))));
+    }
+
+    #[test]
+    fn policy_limits_the_choice() {
+        let policy = Policy { min_nist_level: 3, require_ind_cca: true, ..Policy::default() };
+        assert!(policy.permits(FIRESABER) && !policy.permits(LIGHTSABER) && !policy.permits(ALGORITHM_COUNT));
+
+        // algorithms this build does not implement are never permitted by default
+        assert_eq!(AlgorithmSet::all(), AlgorithmSet::empty().with(FIRESABER));
+        assert!(!policy.permits(SABER) && !Policy::default().permits(KYBER1024));
+
+        let offer = Offer::from_policy(&policy).unwrap();
+        assert_eq!(offer.algorithms(), &[FIRESABER][..]);
+
+        let (alice_container, _) = negotiate(&Policy::default(), &policy, |offer| offer).unwrap();
+        assert_eq!(alice_container.get_algorithm_idx(), FIRESABER);
+
+        let denied = Policy { denied: AlgorithmSet::empty().with(FIRESABER), ..Policy::default() };
+        assert!(matches!(negotiate(&Policy::default(), &denied, |offer| offer), Err(EzError::NegotiationFailed(_))));
+        assert!(matches!(Offer::from_policy(&Policy { denied: AlgorithmSet::all(), ..Policy::default() }), Err(EzError::NegotiationFailed(_))));
+
+        // Bob enforces his own policy on the outcome, including the size of the key he actually received
+        let bob_offer = Offer::from_policy(&Policy::default()).unwrap();
+        let (alice_container, alice_offer) = PostQuantumContainer::new_alice_negotiated(&bob_offer, &Policy::default()).unwrap();
+        let public_key = alice_container.get_public_key();
+        for strict in [denied, Policy { max_public_key_bytes: Some(public_key.len() - 1), ..Policy::default() }] {
+            assert!(matches!(PostQuantumContainer::new_bob_negotiated(&bob_offer, &alice_offer, &strict, FIRESABER, public_key), Err(EzError::PolicyViolation(FIRESABER))));
+        }
+    }
+
+    #[test]
+    fn downgrade_is_detected() {
+        // an offer stripped down to algorithms without a backend leaves Alice nothing to pick
+        assert!(matches!(negotiate(&Policy::default(), &Policy::default(), |_| Offer::new(&[LIGHTSABER]).unwrap()), Err(EzError::NegotiationFailed(_))));
+
+        // Bob refuses a choice without a backend, even before checking it against the offers
+        let bob_offer = Offer::from_policy(&Policy::default()).unwrap();
+        let (alice_container, alice_offer) = PostQuantumContainer::new_alice_negotiated(&bob_offer, &Policy::default()).unwrap();
+        assert!(matches!(PostQuantumContainer::new_bob_negotiated(&bob_offer, &alice_offer, &Policy::default(), SABER, alice_container.get_public_key()), Err(EzError::UnsupportedAlgorithm(SABER))));
+
+        // an altered offer that leads to the same choice gets past Bob's check, but the peers then derive different keys
+        let pad = |offer: Offer| {
+            let mut algorithms = vec![LIGHTSABER];
+            algorithms.extend_from_slice(offer.algorithms());
+            Offer::new(&algorithms).unwrap()
+        };
+
+        let (mut alice_container, alice_offer) = PostQuantumContainer::new_alice_negotiated(&pad(bob_offer.clone()), &Policy::default()).unwrap();
+        let bob_container = PostQuantumContainer::new_bob_negotiated(&bob_offer, &alice_offer, &Policy::default(), alice_container.get_algorithm_idx(), alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        assert!(!bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+        let ciphertext = alice_container.encrypt(b"hello", NONCE).unwrap();
+        assert!(matches!(bob_container.decrypt(&ciphertext, NONCE), Err(EzError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn malformed_offers() {
+        assert!(Offer::new(&[]).is_err());
+        assert!(Offer::new(&[SABER, SABER]).is_err());
+        assert!(matches!(Offer::new(&[ALGORITHM_COUNT]), Err(EzError::UnsupportedAlgorithm(_))));
+
+        let bytes = Offer::new(&[SABER, KYBER768]).unwrap().to_bytes();
+        assert_eq!(bytes, [1, 2, SABER, KYBER768]);
+        assert!(matches!(Offer::from_bytes(&bytes[..3]), Err(EzError::InvalidLength { .. })));
+        assert!(matches!(Offer::from_bytes(&[2, 1, SABER]), Err(EzError::UnsupportedVersion(2))));
+        assert!(matches!(Offer::from_bytes(&[1]), Err(EzError::InvalidLength { .. })));
+    }
+}
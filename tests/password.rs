@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::password::{PasswordParams, MAX_MEMORY_KIB};
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, PQNode};
+
+    // cheap parameters keep the tests fast in debug builds
+    const PARAMS: PasswordParams = PasswordParams { memory_kib: 64, iterations: 1, parallelism: 1 };
+
+    #[test]
+    fn round_trip_long_term_keypair() {
+        let alice_container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let encrypted = alice_container.serialize_encrypted_with_params("correct horse", &PARAMS).unwrap();
+
+        // the secret key must not appear in the output
+        let secret_key = alice_container.get_secret_key().unwrap();
+        assert!(!encrypted.windows(secret_key.len()).any(|window| window == secret_key));
+
+        let restored = PostQuantumContainer::deserialize_encrypted(&encrypted, "correct horse").unwrap();
+        assert_eq!(restored.get_node_type(), PQNode::Alice);
+        assert_eq!(restored.get_public_key(), alice_container.get_public_key());
+        assert_eq!(restored.get_secret_key().unwrap(), secret_key);
+
+        // encrypting twice uses a fresh salt and nonce
+        assert_ne!(alice_container.serialize_encrypted_with_params("correct horse", &PARAMS).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn round_trip_after_exchange() {
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm_dictionary::FIRESABER, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+
+        let encrypted = bob_container.serialize_encrypted_with_params(b"hunter2", &PARAMS).unwrap();
+        let restored = PostQuantumContainer::deserialize_encrypted(&encrypted, b"hunter2").unwrap();
+        assert!(alice_container.verify_key_confirmation(&restored.key_confirmation().unwrap()));
+    }
+
+    #[test]
+    fn wrong_password_and_tampering() {
+        let alice_container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let encrypted = alice_container.serialize_encrypted_with_params("correct horse", &PARAMS).unwrap();
+
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&encrypted, "battery staple"), Err(EzError::AuthenticationFailed)));
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&encrypted, ""), Err(EzError::AuthenticationFailed)));
+
+        // the parameters and salt are authenticated, so weakening them is detected
+        let mut weakened = encrypted.clone();
+        weakened[9] ^= 1;
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&weakened, "correct horse"), Err(EzError::AuthenticationFailed)));
+
+        let mut corrupt = encrypted.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&corrupt, "correct horse"), Err(EzError::AuthenticationFailed)));
+
+        for len in 0..40 {
+            assert!(PostQuantumContainer::deserialize_encrypted(&encrypted[..len], "correct horse").is_err());
+        }
+
+        // plaintext serializations are not accepted here
+        let plaintext = alice_container.serialize_to_vector().unwrap();
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&plaintext, "correct horse"), Err(EzError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn parameter_limits() {
+        let alice_container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let too_expensive = PasswordParams { memory_kib: MAX_MEMORY_KIB + 1, ..PARAMS };
+        assert!(matches!(alice_container.serialize_encrypted_with_params("pw", &too_expensive), Err(EzError::LimitExceeded(_))));
+        let invalid = PasswordParams { memory_kib: 1, ..PARAMS };
+        assert!(matches!(alice_container.serialize_encrypted_with_params("pw", &invalid), Err(EzError::InvalidInput(_))));
+
+        // a crafted file demanding unbounded memory is rejected before any hashing
+        let mut encrypted = alice_container.serialize_encrypted_with_params("pw", &PARAMS).unwrap();
+        encrypted[6..10].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&encrypted, "pw"), Err(EzError::LimitExceeded(_))));
+
+        let mut future_version = alice_container.serialize_encrypted_with_params("pw", &PARAMS).unwrap();
+        future_version[4] = 2;
+        assert!(matches!(PostQuantumContainer::deserialize_encrypted(&future_version, "pw"), Err(EzError::UnsupportedVersion(2))));
+    }
+}
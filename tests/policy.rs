@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use ez_pqcrypto::algorithm_dictionary::*;
+    use ez_pqcrypto::ez_error::EzError;
+    use ez_pqcrypto::negotiation::{AlgorithmSet, Policy};
+    use ez_pqcrypto::PostQuantumContainer;
+
+    /// Level 3 or above, IND-CCA only, and no multi-megabyte McEliece keys
+    fn compliance_policy() -> Policy {
+        Policy { min_nist_level: 3, require_ind_cca: true, max_public_key_bytes: Some(64 * 1024), ..Policy::default() }
+    }
+
+    #[test]
+    fn alice_samples_within_policy() {
+        let policy = compliance_policy();
+        let permitted = policy.permitted();
+        assert_eq!(permitted, AlgorithmSet::empty().with(FIRESABER));
+        assert!(!permitted.contains(LIGHTSABER) && !permitted.contains(PAPABEAREPHEM) && !permitted.contains(MCELIECE6960119));
+
+        for _ in 0..32 {
+            let alice_container = PostQuantumContainer::new_alice_with_policy(&policy).unwrap();
+            assert!(permitted.contains(alice_container.get_algorithm_idx()));
+        }
+
+        // allowing an algorithm this build does not implement does not make it available
+        let only_saber = Policy { allowed: AlgorithmSet::empty().with(SABER), ..policy };
+        assert!(!only_saber.permits(SABER));
+        assert_eq!(only_saber.permitted(), AlgorithmSet::empty());
+        assert!(matches!(PostQuantumContainer::new_alice_with_policy(&only_saber), Err(EzError::InvalidInput(_))));
+        let nothing = Policy { denied: AlgorithmSet::empty().with(FIRESABER), ..policy };
+        assert_eq!(nothing.permitted(), AlgorithmSet::empty());
+        assert!(matches!(PostQuantumContainer::new_alice_with_policy(&nothing), Err(EzError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn bob_rejects_choices_outside_policy() {
+        let policy = compliance_policy();
+        let alice_container = PostQuantumContainer::new_alice(Some(FIRESABER)).unwrap();
+        let public_key = alice_container.get_public_key();
+        assert!(PostQuantumContainer::new_bob_with_policy(&policy, FIRESABER, public_key).is_ok());
+
+        for algorithm in [LIGHTSABER, BABYBEAREPHEM, MCELIECE8192128, SABER] {
+            assert!(matches!(PostQuantumContainer::new_bob_with_policy(&Policy::default(), algorithm, public_key), Err(EzError::UnsupportedAlgorithm(rejected)) if rejected == algorithm));
+        }
+
+        let denied = Policy { denied: AlgorithmSet::empty().with(FIRESABER), ..policy };
+        assert!(matches!(PostQuantumContainer::new_bob_with_policy(&denied, FIRESABER, public_key), Err(EzError::PolicyViolation(_))));
+        assert!(matches!(PostQuantumContainer::new_bob_with_policy(&policy, ALGORITHM_COUNT, public_key), Err(EzError::UnsupportedAlgorithm(_))));
+    }
+
+    #[test]
+    fn oversized_public_key_is_refused() {
+        let alice_container = PostQuantumContainer::new_alice(Some(FIRESABER)).unwrap();
+        let mut public_key = alice_container.get_public_key().to_vec();
+        let policy = Policy { max_public_key_bytes: Some(public_key.len()), ..compliance_policy() };
+        assert!(PostQuantumContainer::new_bob_with_policy(&policy, FIRESABER, &public_key).is_ok());
+
+        // the limit applies to the key received, not to the length the scheme specifies
+        public_key.resize(64 * 1024 + 1, 0);
+        for policy in [policy, compliance_policy()] {
+            assert!(matches!(PostQuantumContainer::new_bob_with_policy(&policy, FIRESABER, &public_key), Err(EzError::PolicyViolation(FIRESABER))));
+        }
+
+        let tight = Policy { max_public_key_bytes: Some(public_key_bytes(FIRESABER).unwrap() - 1), ..Policy::default() };
+        assert!(matches!(PostQuantumContainer::new_alice_with_policy(&tight), Err(EzError::InvalidInput(_))));
+        assert!(matches!(PostQuantumContainer::new_bob_with_policy(&tight, FIRESABER, alice_container.get_public_key()), Err(EzError::PolicyViolation(FIRESABER))));
+    }
+
+    #[test]
+    fn dictionary_matches_bindings() {
+        use pqcrypto::kem::{firesaber, lightsaber, saber};
+
+        assert_eq!(public_key_bytes(FIRESABER), Some(firesaber::public_key_bytes()));
+        assert_eq!(public_key_bytes(LIGHTSABER), Some(lightsaber::public_key_bytes()));
+        assert_eq!(public_key_bytes(SABER), Some(saber::public_key_bytes()));
+        assert!((0..ALGORITHM_COUNT).all(|algorithm| public_key_bytes(algorithm).is_some() && nist_level(algorithm).is_some()));
+        assert_eq!(public_key_bytes(ALGORITHM_COUNT), None);
+    }
+}
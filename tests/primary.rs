@@ -1,10 +1,12 @@
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use bytes::{BufMut, BytesMut};
     use rand::prelude::ThreadRng;
     use rand::RngCore;
 
-    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, NONCE_LENGTH_BYTES};
+    use std::io::IoSlice;
+    use ez_pqcrypto::hazmat::SecretAccess;
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, NONCE_LENGTH_BYTES, PACKET_OVERHEAD_BYTES};
 
     /*
         #[test]
@@ -24,19 +26,18 @@ mod tests {
         let mut working = Vec::new();
         for algorithm in 0..algorithm_dictionary::ALGORITHM_COUNT {
             // Good: 0, 1, 2, 7, 8, 9, 10 -> =15, 18, 19, 20, 36 -> =41
-            if algorithm > 35 {
-                if run(Some(algorithm)).is_ok() {
+            if algorithm > 35
+                && run(Some(algorithm)).is_ok() {
                     println!("Good: {}", algorithm);
                     working.push(algorithm);
                 }
-            }
         }
 
         print!("working: [");
         for good in working {
             print!("{}, ", good);
         }
-        print!("]\n")
+        println!("]")
     }
 
     #[test]
@@ -47,7 +48,7 @@ mod tests {
     fn run(algorithm: Option<u8>) -> Result<(), Box<dyn std::error::Error>> {
         let algorithm = algorithm.unwrap_or(algorithm_dictionary::FIRESABER);
         // Alice wants to share data with Bob. She first creates a PostQuantumContainer
-        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm));
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
         // Then, alice sends her public key to Bob. She must also send the byte value of algorithm_dictionary::BABYBEAR to him
         let alice_public_key = alice_container.get_public_key();
         let algorithm_byte_value = alice_container.get_algorithm_idx();
@@ -71,7 +72,7 @@ mod tests {
         assert_ne!(eve_ss, bob_ss);
 
         let plaintext = b"Hello, world!";
-        let nonce = b"unique nonceunique nonce"; // 96 bits or 12 bytes
+        let nonce = &b"unique nonceunique nonce"[..NONCE_LENGTH_BYTES]; // 96 bits (AES-GCM-SIV) or 192 bits (XChaCha20)
 
         let ciphertext = alice_container.encrypt(plaintext, nonce).unwrap();
         let decrypted = bob_container.decrypt(ciphertext, nonce).unwrap();
@@ -87,7 +88,7 @@ mod tests {
 
         let algorithm = algorithm_dictionary::FIRESABER;
         println!("Test algorithm {}", algorithm);
-        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm));
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
         let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
         alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
 
@@ -98,11 +99,11 @@ mod tests {
 
         println!("[ {} ] {:?}", buf.len(), &buf[..]);
         let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
-        alice_container.protect_packet_in_place(HEADER_LEN, &mut buf, &nonce).unwrap();
+        alice_container.protect_packet_in_place(HEADER_LEN, &mut buf, nonce).unwrap();
 
         println!("[ {} ] {:?}", buf.len(), &buf[..]);
         let mut header = buf.split_to(HEADER_LEN);
-        bob_container.validate_packet_in_place(&header, &mut buf, &nonce).unwrap();
+        bob_container.validate_packet_in_place(&header, &mut buf, nonce).unwrap();
         header.unsplit(buf);
         let buf = header;
 
@@ -116,7 +117,7 @@ mod tests {
 
         let algorithm = algorithm_dictionary::FIRESABER;
         println!("Test algorithm {}", algorithm);
-        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm));
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
         let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
         alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
 
@@ -130,27 +131,27 @@ mod tests {
 
             println!("[ {} ] {:?}", buf.len(), &buf[..]);
             let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
-            alice_container.protect_packet_in_place(HEADER_LEN, &mut buf, &nonce).unwrap();
-            alice_container.protect_packet_in_place(HEADER_LEN, &mut buf2, &nonce).unwrap();
+            alice_container.protect_packet_in_place(HEADER_LEN, &mut buf, nonce).unwrap();
+            alice_container.protect_packet_in_place(HEADER_LEN, &mut buf2, nonce).unwrap();
 
             // pretend someone grabs the header + ciphertext
             let mut intercepted_packet = buf.clone();
 
             // to simulate out-of order delivery, protect a new packet in place and validate that one
             println!("[ {} ] {:?}", buf2.len(), &buf2[..]);
-            let mut header2 = buf2.split_to(HEADER_LEN);
-            assert!(bob_container.validate_packet_in_place(&header2, &mut buf2, &nonce).is_err());
+            let header2 = buf2.split_to(HEADER_LEN);
+            assert!(bob_container.validate_packet_in_place(&header2, &mut buf2, nonce).is_err());
             // now do them in order
 
             let mut header = buf.split_to(HEADER_LEN);
-            bob_container.validate_packet_in_place(&header, &mut buf, &nonce).unwrap();
+            bob_container.validate_packet_in_place(&header, &mut buf, nonce).unwrap();
             // since we are using in-place decryption, the first attempt will corrupt the payload, thus invalidating the packet's
             // decryption operation, even though it may correct. As such, this proves it is NECESSARY that packets
             // arrive IN-ORDER!!
-            assert!(bob_container.validate_packet_in_place(&header2, &mut buf2, &nonce).is_err());
+            assert!(bob_container.validate_packet_in_place(&header2, &mut buf2, nonce).is_err());
             // now, let's see what happens when we try validating the intercepted packet (replay attack)
             let intercepted_header = intercepted_packet.split_to(HEADER_LEN);
-            assert!(bob_container.validate_packet_in_place(&intercepted_header, &mut intercepted_packet, &nonce).is_err());
+            assert!(bob_container.validate_packet_in_place(&intercepted_header, &mut intercepted_packet, nonce).is_err());
             // Therefore: packets MUST be in order, and repeat attempts will invalidate the decryption attempt, as desired
             header.unsplit(buf);
             let buf = header;
@@ -159,6 +160,243 @@ mod tests {
         }
     }
 
+    #[test]
+    fn key_types_zeroize() {
+        use pqcrypto::kem::firesaber;
+        use pqcrypto::traits::kem::{SecretKey, SharedSecret};
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<firesaber::SecretKey>();
+        assert_zeroize_on_drop::<firesaber::SharedSecret>();
+        assert_zeroize_on_drop::<ez_pqcrypto::export::PostQuantumExport>();
+
+        let (pk, mut sk) = firesaber::keypair();
+        let (mut ss, _ct) = firesaber::encapsulate(&pk);
+        sk.zeroize();
+        ss.zeroize();
+        assert!(sk.as_bytes().iter().all(|byte| *byte == 0));
+        assert!(ss.as_bytes().iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        use subtle::ConstantTimeEq;
+        use pqcrypto::kem::{firesaber, lightsaber, saber};
+        use pqcrypto::traits::kem::{Ciphertext, PublicKey, SecretKey};
+
+        macro_rules! check_scheme {
+            ($scheme:ident) => {{
+                let (pk, sk) = $scheme::keypair();
+                let (ss, ct) = $scheme::encapsulate(&pk);
+                let (other_pk, other_sk) = $scheme::keypair();
+                let (other_ss, other_ct) = $scheme::encapsulate(&other_pk);
+
+                assert!(bool::from(pk.ct_eq(&$scheme::PublicKey::from_bytes(pk.as_bytes()).unwrap())));
+                assert!(bool::from(sk.ct_eq(&$scheme::SecretKey::from_bytes(sk.as_bytes()).unwrap())));
+                assert!(bool::from(ct.ct_eq(&$scheme::Ciphertext::from_bytes(ct.as_bytes()).unwrap())));
+                assert!(bool::from(ss.ct_eq(&$scheme::decapsulate(&ct, &sk))));
+
+                assert!(!bool::from(pk.ct_eq(&other_pk)));
+                assert!(!bool::from(sk.ct_eq(&other_sk)));
+                assert!(!bool::from(ct.ct_eq(&other_ct)));
+                assert!(!bool::from(ss.ct_eq(&other_ss)));
+                assert!(pk != other_pk && sk != other_sk && ct != other_ct && ss != other_ss);
+            }};
+        }
+
+        check_scheme!(firesaber);
+        check_scheme!(lightsaber);
+        check_scheme!(saber);
+
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        assert!(!alice_container.shared_secret_eq(&[0u8; 32]));
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        assert!(alice_container.shared_secret_eq(bob_container.get_shared_secret().unwrap()));
+        assert!(!alice_container.shared_secret_eq(&[0u8; 32]));
+        assert!(!alice_container.shared_secret_eq(&[]));
+    }
+
+    #[test]
+    fn high_level_secret_operations() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        assert!(alice_container.key_confirmation().is_err());
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+
+        assert_eq!(alice_container.public_key_fingerprint(), bob_container.public_key_fingerprint());
+        let confirmation = bob_container.key_confirmation().unwrap();
+        assert_eq!(alice_container.key_confirmation().unwrap(), confirmation);
+        assert!(alice_container.verify_key_confirmation(&confirmation));
+        assert!(!alice_container.verify_key_confirmation(&[0u8; 32]));
+
+        let alice_key = alice_container.export_keying_material(b"label", b"context", 48).unwrap();
+        assert_eq!(alice_key, bob_container.export_keying_material(b"label", b"context", 48).unwrap());
+        assert_ne!(alice_key, alice_container.export_keying_material(b"other label", b"context", 48).unwrap());
+        assert_ne!(&alice_key[..32], alice_container.get_shared_secret().unwrap());
+
+        // Debug must not reveal any secret material
+        let secret_key = alice_container.get_secret_key().unwrap();
+        let secret_hex = secret_key[..16].iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+        let debug = format!("{:?}", alice_container);
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&secret_hex));
+        assert!(!debug.contains(&format!("{:?}", &secret_key[..16])));
+        assert!(!format!("{:?}", pqcrypto::kem::firesaber::keypair().1).contains(", "));
+    }
+
+    #[test]
+    fn structured_errors() {
+        use std::error::Error;
+        use ez_pqcrypto::ez_error::EzError;
+
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
+        assert!(matches!(alice_container.encrypt(b"early", nonce), Err(EzError::HandshakeIncomplete { .. })));
+        assert!(matches!(alice_container.get_ciphertext(), Err(EzError::HandshakeIncomplete { missing: "ciphertext" })));
+
+        let public_key = alice_container.get_public_key();
+        match PostQuantumContainer::new_bob(algorithm, &public_key[1..]) {
+            Err(EzError::InvalidLength { expected, actual, .. }) => assert_eq!(expected, actual + 1),
+            other => panic!("unexpected result: {:?}", other.map(|_| ()))
+        }
+
+        let bob_container = PostQuantumContainer::new_bob(algorithm, public_key).unwrap();
+        assert!(matches!(bob_container.get_secret_key(), Err(EzError::WrongRole { .. })));
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+
+        let mut ciphertext = alice_container.encrypt(b"hello", nonce).unwrap();
+        ciphertext[0] ^= 1;
+        assert!(matches!(bob_container.decrypt(&ciphertext, nonce), Err(EzError::AuthenticationFailed)));
+
+        let mut packet = BytesMut::new();
+        packet.put_slice(b"headerpayload");
+        alice_container.protect_packet_in_place(6, &mut packet, nonce).unwrap();
+        let mut payload = packet.split_off(6);
+        let mut replay = payload.clone();
+        bob_container.validate_packet_in_place(&packet, &mut payload, nonce).unwrap();
+        assert!(matches!(bob_container.validate_packet_in_place(&packet, &mut replay, nonce), Err(EzError::ReplayDetected)));
+
+        let err = PostQuantumContainer::deserialize_from_bytes([1, 2, 3]).unwrap_err();
+        assert!(matches!(err, EzError::DeserializeError { .. }));
+        assert!(err.source().is_some());
+        assert!(!err.to_string().is_empty());
+    }
+
+    #[test]
+    fn in_slice() {
+        const HEADER_LEN: usize = 10;
+        const PACKET_LEN: usize = HEADER_LEN + 40;
+
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
+
+        let mut buffer = [0u8; PACKET_LEN + PACKET_OVERHEAD_BYTES];
+        for (idx, byte) in buffer[..PACKET_LEN].iter_mut().enumerate() {
+            *byte = idx as u8;
+        }
+        let original = buffer;
+
+        // one byte short of the required tail room
+        assert!(alice_container.protect_packet_in_slice(HEADER_LEN, &mut buffer[..PACKET_LEN + PACKET_OVERHEAD_BYTES - 1], PACKET_LEN, nonce).is_err());
+        assert_eq!(buffer, original);
+
+        let protected_len = alice_container.protect_packet_in_slice(HEADER_LEN, &mut buffer, PACKET_LEN, nonce).unwrap();
+        assert_eq!(protected_len, PACKET_LEN + PACKET_OVERHEAD_BYTES);
+
+        let mut replay = buffer;
+        let (header, payload) = buffer.split_at_mut(HEADER_LEN);
+        let plaintext_len = bob_container.validate_packet_in_slice(&header, payload, nonce).unwrap();
+        assert_eq!(&payload[..plaintext_len], &original[HEADER_LEN..PACKET_LEN]);
+
+        let (header, payload) = replay.split_at_mut(HEADER_LEN);
+        assert!(bob_container.validate_packet_in_slice(&header, payload, nonce).is_err());
+        assert!(bob_container.validate_packet_in_slice(&header, &mut [0u8; 4], nonce).is_err());
+    }
+
+    #[test]
+    fn detached() {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
+
+        let (ciphertext, tag) = alice_container.encrypt_detached(b"hello, world!", nonce).unwrap();
+        let attached = alice_container.encrypt(b"hello, world!", nonce).unwrap();
+        assert_eq!(ciphertext.len(), 13);
+        assert_eq!([&ciphertext[..], &tag[..]].concat(), attached);
+        assert_eq!(bob_container.decrypt_detached(&ciphertext, &tag, nonce).unwrap().as_slice(), b"hello, world!");
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 1;
+        assert!(bob_container.decrypt_detached(&ciphertext, &bad_tag, nonce).is_err());
+
+        let mut buffer = *b"in place";
+        let tag = alice_container.encrypt_in_place_detached(&mut buffer, b"aad", nonce).unwrap();
+        assert!(bob_container.decrypt_in_place_detached(&mut buffer.clone(), b"other aad", &tag, nonce).is_err());
+        bob_container.decrypt_in_place_detached(&mut buffer, b"aad", &tag, nonce).unwrap();
+        assert_eq!(&buffer, b"in place");
+
+        let mut packet = BytesMut::new();
+        packet.put_slice(b"headerpayload");
+        let tag = alice_container.protect_packet_in_place_detached(6, &mut packet, nonce).unwrap();
+        assert_eq!(packet.len(), 13 + 8);
+        let mut payload = packet.split_off(6);
+        let mut replay = payload.clone();
+        bob_container.validate_packet_in_place_detached(&packet, &mut payload, &tag, nonce).unwrap();
+        assert_eq!(&payload[..], b"payload");
+        assert!(bob_container.validate_packet_in_place_detached(&packet, &mut replay, &tag, nonce).is_err());
+    }
+
+    #[test]
+    fn vectored() {
+        use ez_pqcrypto::ez_error::EzError;
+
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+
+        let header_parts: [&[u8]; 2] = [b"header part one", b"header part two"];
+        let header = header_parts.iter().map(|part| IoSlice::new(part)).collect::<Vec<_>>();
+        let nonce: [u8; NONCE_LENGTH_BYTES] = Default::default();
+
+        let plaintext = vec![7u8; 100];
+        let mut payload = plaintext.clone();
+        let (tag, pid) = alice_container.protect_packet_vectored(&header, &mut payload, nonce).unwrap();
+        assert_eq!(payload.len(), plaintext.len());
+        assert_ne!(plaintext, payload);
+
+        // a modified header, or a PID other than the one sent with the packet, fails and zeroes the payload
+        let mut forged = payload.clone();
+        assert!(bob_container.validate_packet_vectored(&[IoSlice::new(b"forged header")], &mut forged, &tag, pid, nonce).is_err());
+        assert!(forged.iter().all(|byte| *byte == 0));
+        let mut forged = payload.clone();
+        assert!(bob_container.validate_packet_vectored(&header, &mut forged, &tag, pid + 1, nonce).is_err());
+
+        let mut replay = payload.clone();
+        bob_container.validate_packet_vectored(&header, &mut payload, &tag, pid, nonce).unwrap();
+        assert_eq!(plaintext, payload);
+        assert!(matches!(bob_container.validate_packet_vectored(&header, &mut replay, &tag, pid, nonce), Err(EzError::ReplayDetected)));
+
+        // the PID travels with the packet, so the receiver does not have to guess it
+        let mut payloads = (0..3u8).map(|idx| vec![idx; 32]).collect::<Vec<_>>();
+        let sent = payloads.iter_mut().map(|payload| alice_container.protect_packet_vectored(&header, payload, nonce).unwrap()).collect::<Vec<_>>();
+        assert_eq!(sent.iter().map(|(_, pid)| *pid).collect::<Vec<_>>(), [pid + 1, pid + 2, pid + 3]);
+        for (idx, (payload, (tag, pid))) in payloads.iter_mut().zip(sent.iter()).enumerate() {
+            bob_container.validate_packet_vectored(&header, payload, tag, *pid, nonce).unwrap();
+            assert_eq!(payload, &vec![idx as u8; 32]);
+        }
+    }
+
     /*
     #[test]
     // for asymmetric crypto (public crypto)
@@ -186,15 +424,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn alice_with_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let alice_container = PostQuantumContainer::new_alice_with_rng(None, &mut StdRng::seed_from_u64(7)).unwrap();
+        let same_rng = PostQuantumContainer::new_alice_with_rng(None, &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(alice_container.get_algorithm_idx(), same_rng.get_algorithm_idx());
+        assert_eq!(alice_container.get_public_key(), same_rng.get_public_key());
+        assert_eq!(alice_container.get_secret_key().unwrap(), same_rng.get_secret_key().unwrap());
+
+        let other_rng = PostQuantumContainer::new_alice_with_rng(Some(algorithm_dictionary::FIRESABER), &mut StdRng::seed_from_u64(8)).unwrap();
+        assert_ne!(alice_container.get_public_key(), other_rng.get_public_key());
+        assert!(PostQuantumContainer::new_alice_with_rng(Some(algorithm_dictionary::ALGORITHM_COUNT), &mut rand::rngs::OsRng).is_err());
+
+        // the RNG is only used for the duration of the call
+        assert_ne!(PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap().get_public_key(), alice_container.get_public_key());
+    }
+
+    #[test]
+    fn alice_from_seed() {
+        use ez_pqcrypto::SEED_LENGTH_BYTES;
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let seed = [42u8; SEED_LENGTH_BYTES];
+
+        let alice_container = PostQuantumContainer::new_alice_from_seed(algorithm, &seed).unwrap();
+        let same_seed = PostQuantumContainer::new_alice_from_seed(algorithm, &seed).unwrap();
+        assert_eq!(alice_container.get_public_key(), same_seed.get_public_key());
+        assert_eq!(alice_container.get_secret_key().unwrap(), same_seed.get_secret_key().unwrap());
+
+        let other_seed = PostQuantumContainer::new_alice_from_seed(algorithm, &[43u8; SEED_LENGTH_BYTES]).unwrap();
+        assert_ne!(alice_container.get_public_key(), other_seed.get_public_key());
+        assert!(PostQuantumContainer::new_alice_from_seed(algorithm_dictionary::ALGORITHM_COUNT, &seed).is_err());
+
+        // the exchange itself stays randomized
+        let mut alice_container = alice_container;
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        let other_bob = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        assert_ne!(bob_container.get_ciphertext().unwrap(), other_bob.get_ciphertext().unwrap());
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        assert!(bob_container.verify_key_confirmation(&alice_container.key_confirmation().unwrap()));
+    }
+
     #[test]
     fn test_serialize_deserialize() {
         let algorithm = algorithm_dictionary::FIRESABER;
         println!("Test algorithm {}", algorithm);
-        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm));
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
         let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
         alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
 
-        let nonce = &mut [0u8; 12];
+        let nonce = &mut [0u8; NONCE_LENGTH_BYTES];
         ThreadRng::default().fill_bytes(nonce);
         let msg = "hello, world!";
 
@@ -204,8 +485,8 @@ mod tests {
         let al_ss0 = alice_container.get_shared_secret().unwrap();
         let al_secr0 = alice_container.get_secret_key().unwrap();
 
-        let bob_pub0 = alice_container.get_public_key();
-        let bob_ss0 = alice_container.get_shared_secret().unwrap();
+        let bob_pub0 = bob_container.get_public_key();
+        let bob_ss0 = bob_container.get_shared_secret().unwrap();
         let _bob_secr0 = alice_container.get_secret_key().unwrap();
 
         let serialized_alice = alice_container.serialize_to_vector().unwrap();
@@ -228,7 +509,9 @@ mod tests {
         assert_eq!(bob_pub0, bob_pub1);
         assert_eq!(bob_ss0, bob_ss1);
 
-        assert_ne!(al_pub0, bob_pub0);
+        // Bob encapsulates against Alice's public key, and both sides hold the same shared secret
+        assert_eq!(al_pub0, bob_pub0);
+        assert_eq!(al_ss0, bob_ss0);
 
         let _decr_alice = alice_container.decrypt(&enc, &nonce).unwrap();
         let _decr_bob = bob_container.decrypt(&enc, &nonce).unwrap();
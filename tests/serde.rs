@@ -0,0 +1,91 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use ez_pqcrypto::export::PostQuantumExport;
+    use ez_pqcrypto::hazmat::{SecretAccess, SecretExport};
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer, PQNode};
+    use pqcrypto::kem::firesaber;
+    use pqcrypto::traits::kem::PublicKey;
+    use std::convert::TryFrom;
+
+    fn exchanged_containers() -> (PostQuantumContainer, PostQuantumContainer) {
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm_dictionary::FIRESABER)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm_dictionary::FIRESABER, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        (alice_container, bob_container)
+    }
+
+    #[test]
+    fn json_uses_base64() {
+        let (alice_container, bob_container) = exchanged_containers();
+        let json = serde_json::to_value(PostQuantumExport::from(&bob_container)).unwrap();
+
+        assert_eq!(json["node"], "Bob");
+        assert_eq!(json["public_key"].as_str().unwrap(), STANDARD.encode(bob_container.get_public_key()));
+        assert_eq!(json["ciphertext"].as_str().unwrap(), STANDARD.encode(bob_container.get_ciphertext().unwrap()));
+        assert!(json["secret_key"].is_null());
+        // the secrets are only serialized through hazmat
+        assert!(json["shared_secret"].is_null());
+        let export: PostQuantumExport = serde_json::from_value(json).unwrap();
+        assert!(PostQuantumContainer::try_from(export).unwrap().get_shared_secret().is_err());
+
+        let json = serde_json::to_value(SecretExport(PostQuantumExport::from(&bob_container))).unwrap();
+        assert_eq!(json["shared_secret"].as_str().unwrap(), STANDARD.encode(bob_container.get_shared_secret().unwrap()));
+        // and are dropped when read back without it
+        let export: PostQuantumExport = serde_json::from_value(json.clone()).unwrap();
+        assert!(PostQuantumContainer::try_from(export).unwrap().get_shared_secret().is_err());
+
+        let export = serde_json::from_value::<SecretExport>(json).unwrap().0;
+        let restored = PostQuantumContainer::try_from(export).unwrap();
+        assert_eq!(restored.get_node_type(), PQNode::Bob);
+        assert!(alice_container.verify_key_confirmation(&restored.key_confirmation().unwrap()));
+
+        let mut invalid = serde_json::to_value(PostQuantumExport::from(&bob_container)).unwrap();
+        invalid["public_key"] = "not base64!".into();
+        assert!(serde_json::from_value::<PostQuantumExport>(invalid).is_err());
+    }
+
+    #[test]
+    fn binary_formats_use_raw_bytes() {
+        let (alice_container, bob_container) = exchanged_containers();
+        let public_key = alice_container.get_public_key();
+        let secret_key = alice_container.get_secret_key().unwrap();
+
+        let encoded = bincode::serialize(&PostQuantumExport::from(&alice_container)).unwrap();
+        assert!(encoded.windows(public_key.len()).any(|window| window == public_key));
+        assert!(!encoded.windows(secret_key.len()).any(|window| window == secret_key));
+
+        let encoded = bincode::serialize(&SecretExport(PostQuantumExport::from(&alice_container))).unwrap();
+        assert!(encoded.windows(secret_key.len()).any(|window| window == secret_key));
+        let restored = PostQuantumContainer::try_from(bincode::deserialize::<SecretExport>(&encoded).unwrap().0).unwrap();
+        assert_eq!(restored.get_secret_key().unwrap(), secret_key);
+        assert!(bob_container.verify_key_confirmation(&restored.key_confirmation().unwrap()));
+
+        let mut encoded = Vec::new();
+        ciborium::ser::into_writer(&SecretExport(PostQuantumExport::from(&alice_container)), &mut encoded).unwrap();
+        assert!(encoded.windows(public_key.len()).any(|window| window == public_key));
+        let export = ciborium::de::from_reader::<SecretExport, _>(encoded.as_slice()).unwrap().0;
+        let restored = PostQuantumContainer::try_from(export).unwrap();
+        assert_eq!(restored.get_node_type(), PQNode::Alice);
+        assert_eq!(restored.get_secret_key().unwrap(), secret_key);
+    }
+
+    #[test]
+    fn key_types() {
+        let (public_key, _) = firesaber::keypair();
+        let (_, ciphertext) = firesaber::encapsulate(&public_key);
+
+        let json = serde_json::to_string(&public_key).unwrap();
+        assert_eq!(json, format!("\"{}\"", STANDARD.encode(public_key.as_bytes())));
+        assert!(serde_json::from_str::<firesaber::PublicKey>(&json).unwrap() == public_key);
+
+        let encoded = bincode::serialize(&ciphertext).unwrap();
+        assert!(bincode::deserialize::<firesaber::Ciphertext>(&encoded).unwrap() == ciphertext);
+
+        // a value of the wrong length is rejected rather than truncated or padded
+        let short = serde_json::to_string(&STANDARD.encode(&public_key.as_bytes()[1..])).unwrap();
+        assert!(serde_json::from_str::<firesaber::PublicKey>(&short).is_err());
+        assert!(bincode::deserialize::<firesaber::Ciphertext>(&bincode::serialize(&[0u8; 4][..]).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::{Read, Write};
+
+    use ez_pqcrypto::{algorithm_dictionary, PostQuantumContainer};
+    use ez_pqcrypto::stream::{DecryptStream, EncryptStream, NONCE_PREFIX_LENGTH, SEGMENT_LENGTH, TAG_LENGTH_BYTES};
+
+    fn containers() -> (PostQuantumContainer, PostQuantumContainer) {
+        let algorithm = algorithm_dictionary::FIRESABER;
+        let mut alice_container = PostQuantumContainer::new_alice(Some(algorithm)).unwrap();
+        let bob_container = PostQuantumContainer::new_bob(algorithm, alice_container.get_public_key()).unwrap();
+        alice_container.alice_on_receive_ciphertext(bob_container.get_ciphertext().unwrap()).unwrap();
+        (alice_container, bob_container)
+    }
+
+    fn encrypt(container: &PostQuantumContainer, plaintext: &[u8]) -> Vec<u8> {
+        let mut stream = EncryptStream::new(container, Vec::new()).unwrap();
+        // write in uneven pieces to exercise the segment buffering
+        for chunk in plaintext.chunks(1000) {
+            stream.write_all(chunk).unwrap();
+        }
+        stream.finish().unwrap()
+    }
+
+    fn decrypt(container: &PostQuantumContainer, ciphertext: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut stream = DecryptStream::new(container, ciphertext)?;
+        let mut plaintext = Vec::new();
+        stream.read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn stream_round_trip() {
+        let (alice_container, bob_container) = containers();
+
+        for len in &[0, 1, SEGMENT_LENGTH - 1, SEGMENT_LENGTH, SEGMENT_LENGTH + 1, 3 * SEGMENT_LENGTH + 7] {
+            let plaintext = (0..*len).map(|x| x as u8).collect::<Vec<u8>>();
+            let ciphertext = encrypt(&alice_container, &plaintext);
+            let segments = len / SEGMENT_LENGTH + 1;
+            assert_eq!(ciphertext.len(), NONCE_PREFIX_LENGTH + len + (segments * TAG_LENGTH_BYTES));
+
+            let decrypted = decrypt(&bob_container, &ciphertext).unwrap();
+            assert_eq!(plaintext, decrypted);
+        }
+    }
+
+    #[test]
+    fn stream_truncation_detected() {
+        let (alice_container, bob_container) = containers();
+        let plaintext = vec![7u8; 2 * SEGMENT_LENGTH + 100];
+        let ciphertext = encrypt(&alice_container, &plaintext);
+
+        // dropping the final segment leaves a stream that ends on a full, non-last segment
+        let boundary = NONCE_PREFIX_LENGTH + 2 * (SEGMENT_LENGTH + TAG_LENGTH_BYTES);
+        assert!(decrypt(&bob_container, &ciphertext[..boundary]).is_err());
+        assert!(decrypt(&bob_container, &ciphertext[..ciphertext.len() - 1]).is_err());
+
+        let mut extended = ciphertext.clone();
+        extended.push(0);
+        assert!(decrypt(&bob_container, &extended).is_err());
+    }
+
+    #[test]
+    fn stream_tampering_detected() {
+        let (alice_container, bob_container) = containers();
+        let plaintext = vec![7u8; 2 * SEGMENT_LENGTH];
+        let mut ciphertext = encrypt(&alice_container, &plaintext);
+
+        let idx = NONCE_PREFIX_LENGTH + SEGMENT_LENGTH + TAG_LENGTH_BYTES + 10;
+        ciphertext[idx] ^= 1;
+        let mut stream = DecryptStream::new(&bob_container, ciphertext.as_slice()).unwrap();
+        let mut first_segment = vec![0u8; SEGMENT_LENGTH];
+        // the first segment is untouched, so it is released before the error is hit
+        stream.read_exact(&mut first_segment).unwrap();
+        assert!(stream.read(&mut [0u8; 16]).is_err());
+        assert!(stream.read(&mut [0u8; 16]).is_err());
+    }
+}